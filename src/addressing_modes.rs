@@ -1,4 +1,5 @@
 use crate::cpu::CPU;
+use crate::bus::Bus;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[allow(non_camel_case_types)]
@@ -18,7 +19,7 @@ pub enum AddressingMode {
    Implied
 }
 
-pub fn get_operator_from_addressing_mode(cpu: &mut CPU, addressing_mode: AddressingMode) -> u16 {
+pub fn get_operator_from_addressing_mode<B: Bus>(cpu: &mut CPU<B>, addressing_mode: AddressingMode) -> u16 {
     
     let counter = cpu.program_counter + 1;
     match addressing_mode {
@@ -38,11 +39,13 @@ pub fn get_operator_from_addressing_mode(cpu: &mut CPU, addressing_mode: Address
         AddressingMode::Absolute_X => {
             let mem = cpu.load16(counter);
             let address = mem.wrapping_add(cpu.registers.x as u16);
+            cpu.page_crossed = crosses_page(mem, address);
             address
         },
         AddressingMode::Absolute_Y => {
             let mem = cpu.load16(counter);
             let address = mem.wrapping_add(cpu.registers.y as u16);
+            cpu.page_crossed = crosses_page(mem, address);
             address
         }
         AddressingMode::Indirect_X => {
@@ -54,14 +57,30 @@ pub fn get_operator_from_addressing_mode(cpu: &mut CPU, addressing_mode: Address
             (high as u16) << 8 | (low as u16) 
         },
         AddressingMode::Indirect_Y => {
-            let base_ptr = cpu.load(counter);
-            
-            let ptr = (base_ptr as u8).wrapping_add(cpu.registers.y);
+            // Unlike Indirect_X the register is added after the pointer is followed
+            let ptr = cpu.load(counter);
+
             let low = cpu.load(ptr as u16);
             let high = cpu.load(ptr.wrapping_add(1) as u16);
-            (high as u16) << 8 | (low as u16) 
+            let base = (high as u16) << 8 | (low as u16);
+            let address = base.wrapping_add(cpu.registers.y as u16);
+            cpu.page_crossed = crosses_page(base, address);
+            address
+        },
+        AddressingMode::Indirect => {
+            let ptr = cpu.load16(counter);
+
+            // The 6502 never carries into the high byte of the pointer, so JMP ($10FF)
+            //  reads its high byte from $1000 instead of $1100. 
+            let low = cpu.load(ptr);
+            let high = cpu.load((ptr & 0xFF00) | (ptr.wrapping_add(1) & 0x00FF));
+            (high as u16) << 8 | (low as u16)
         }
         _ => panic!()
     }
 
-}
\ No newline at end of file
+}
+
+pub fn crosses_page(base: u16, address: u16) -> bool {
+    base & 0xFF00 != address & 0xFF00
+}
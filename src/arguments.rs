@@ -6,7 +6,16 @@ use std::io::prelude::*;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProgramArguments {
-    pub run_integration_tests: bool
+    pub run_integration_tests: bool,
+    pub disassemble_rom: Option<String>,
+    pub rom_path: Option<String>,
+    pub trace_path: Option<String>,
+    pub illegal_opcode_mode: String,
+    pub record_audio_path: Option<String>,
+    pub sample_rate: u32,
+    pub keymap_path: Option<String>,
+    pub headless: bool,
+    pub frames: Option<u64>
 }
 
 impl ProgramArguments {
@@ -33,10 +42,69 @@ impl ProgramArguments {
                         .long("integration")
                         .takes_value(false)
                         .help("If present, will skip running the emulator and will instead run it's integration tests. "))
+                    .arg(Arg::with_name("disassemble")
+                        .short("d")
+                        .long("disassemble")
+                        .takes_value(true)
+                        .value_name("ROM")
+                        .help("Prints the disassembly of the given ROM's program rom instead of running the emulator. "))
+                    .arg(Arg::with_name("rom")
+                        .index(1)
+                        .value_name("ROM")
+                        .help("The .nes file to run, can also be inside a .zip or a .gz "))
+                    .arg(Arg::with_name("trace")
+                        .short("t")
+                        .long("trace")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Writes a nestest style log of every instruction run to the given file. "))
+                    .arg(Arg::with_name("illegal_opcodes")
+                        .long("illegal-opcodes")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["execute", "log", "trap"])
+                        .default_value("execute")
+                        .help("What to do when the CPU hits an unofficial opcode. "))
+                    .arg(Arg::with_name("record_audio")
+                        .long("record-audio")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Writes everything the APU plays to the given .wav file. "))
+                    .arg(Arg::with_name("sample_rate")
+                        .long("sample-rate")
+                        .takes_value(true)
+                        .value_name("HZ")
+                        .possible_values(&["44100", "48000"])
+                        .default_value("44100")
+                        .help("Sample rate for the audio output. "))
+                    .arg(Arg::with_name("keymap")
+                        .long("keymap")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("JSON file saying which keys press which controller buttons. "))
+                    .arg(Arg::with_name("headless")
+                        .long("headless")
+                        .takes_value(false)
+                        .help("Runs without opening a window. "))
+                    .arg(Arg::with_name("frames")
+                        .long("frames")
+                        .takes_value(true)
+                        .value_name("N")
+                        .validator(|frames| frames.parse::<u64>().map(|_| ()).map_err(|_| "Must be a whole number".to_string()))
+                        .help("Stops after running this many frames. "))
                     .get_matches();    
     
             return Some(ProgramArguments {
-                run_integration_tests: arguments.is_present("integration_tests")
+                run_integration_tests: arguments.is_present("integration_tests"),
+                disassemble_rom: arguments.value_of("disassemble").map(|path| path.to_string()),
+                rom_path: arguments.value_of("rom").map(|path| path.to_string()),
+                trace_path: arguments.value_of("trace").map(|path| path.to_string()),
+                illegal_opcode_mode: arguments.value_of("illegal_opcodes").unwrap().to_string(),
+                record_audio_path: arguments.value_of("record_audio").map(|path| path.to_string()),
+                sample_rate: arguments.value_of("sample_rate").unwrap().parse().unwrap(),
+                keymap_path: arguments.value_of("keymap").map(|path| path.to_string()),
+                headless: arguments.is_present("headless"),
+                frames: arguments.value_of("frames").map(|frames| frames.parse().unwrap())
             })
         }
     }
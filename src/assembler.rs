@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use crate::addressing_modes::AddressingMode;
+use crate::disassembler::instruction_length;
+use crate::util::*;
+
+// Turns 6502 assembly into a flat binary that expects to be loaded at origin. Handles
+//  the syntax the integration tests are written in:
+//
+//  ; comments, anywhere on a line
+//  define name $10        - textual constants, like easy6502
+//  label:                 - on its own line or in front of an instruction
+//  dcb $01, 2, %11        - raw bytes (.byte works as well)
+//  LDA #<label            - low/high byte of a label or constant
+//
+// Numbers are $hex, %binary or decimal. A hex number with one or two digits is taken as
+//  zero page if the instruction has a zero page version, labels are always absolute.
+pub fn assemble(source: &str, origin: u16) -> Result<Vec<u8>, String> {
+    let mut defines: HashMap<String, String> = HashMap::new();
+    let mut labels: HashMap<String, u16> = HashMap::new();
+    let mut statements: Vec<(usize, Statement)> = Vec::new();
+
+    // First pass works out where everything goes so labels can be used before they show up
+    let mut address = origin;
+    for (index, raw_line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message: String| format!("Line {}: {}", line_number, message);
+
+        let mut line = match raw_line.find(';') {
+            Some(comment_start) => &raw_line[..comment_start],
+            None => raw_line
+        }.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        if words.next().unwrap().eq_ignore_ascii_case("define") {
+            let name = words.next().ok_or_else(|| error(String::from("define is missing a name")))?;
+            let value = words.next().ok_or_else(|| error(format!("define {} is missing a value", name)))?;
+            defines.insert(name.to_string(), value.to_string());
+            continue;
+        }
+
+        while let Some(colon) = line.find(':') {
+            let label = line[..colon].trim();
+            if !is_identifier(label) {
+                break;
+            }
+            if labels.insert(label.to_string(), address).is_some() {
+                return Err(error(format!("Label [{}] is defined more than once", label)));
+            }
+            line = line[colon + 1..].trim();
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement(line, &defines).map_err(error)?;
+        address = address.wrapping_add(statement.size());
+        statements.push((line_number, statement));
+    }
+
+    let mut output = Vec::new();
+    let mut address = origin;
+    for (line_number, statement) in statements {
+        let bytes = statement.encode(address, &labels).map_err(|message| format!("Line {}: {}", line_number, message))?;
+        address = address.wrapping_add(bytes.len() as u16);
+        output.extend(bytes);
+    }
+
+    Ok(output)
+}
+
+enum Statement {
+    Instruction { opcode: u8, mode: AddressingMode, operand: Option<Expression> },
+    Bytes(Vec<Expression>)
+}
+
+impl Statement {
+    fn size(&self) -> u16 {
+        match self {
+            Statement::Instruction { mode, .. } => instruction_length(*mode),
+            Statement::Bytes(values) => values.len() as u16
+        }
+    }
+
+    fn encode(&self, address: u16, labels: &HashMap<String, u16>) -> Result<Vec<u8>, String> {
+        match self {
+            Statement::Bytes(values) => values.iter().map(|value| {
+                let value = value.resolve(labels)?;
+                if value > 0xFF {
+                    return Err(format!("Value ${:X} does not fit in a byte", value));
+                }
+                Ok(value as u8)
+            }).collect(),
+            Statement::Instruction { opcode, mode, operand } => {
+                let mut bytes = vec![*opcode];
+                let value = match operand {
+                    Some(expression) => expression.resolve(labels)?,
+                    None => return Ok(bytes)
+                };
+
+                match mode {
+                    AddressingMode::Relative => {
+                        let offset = value as i32 - (address as i32 + 2);
+                        if offset < i8::MIN as i32 || offset > i8::MAX as i32 {
+                            return Err(format!("Branch target ${:04X} is too far away", value));
+                        }
+                        bytes.push(offset as i8 as u8);
+                    },
+                    _ if instruction_length(*mode) == 2 => {
+                        if value > 0xFF {
+                            return Err(format!("${:04X} does not fit in a byte", value));
+                        }
+                        bytes.push(value as u8);
+                    },
+                    _ => {
+                        bytes.push(value as u8);
+                        bytes.push((value >> 8) as u8);
+                    }
+                }
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+enum Term {
+    Number(u16),
+    Label(String)
+}
+
+enum Selector {
+    Whole,
+    Low,
+    High
+}
+
+struct Expression {
+    term: Term,
+    selector: Selector,
+    is_byte: bool
+}
+
+impl Expression {
+    fn resolve(&self, labels: &HashMap<String, u16>) -> Result<u16, String> {
+        let value = match &self.term {
+            Term::Number(value) => *value,
+            Term::Label(name) => *labels.get(name).ok_or_else(|| format!("Unknown label [{}]", name))?
+        };
+
+        Ok(match self.selector {
+            Selector::Whole => value,
+            Selector::Low => value & 0x00FF,
+            Selector::High => value >> 8
+        })
+    }
+}
+
+fn parse_statement(line: &str, defines: &HashMap<String, String>) -> Result<Statement, String> {
+    let (mnemonic, operand) = match line.find(char::is_whitespace) {
+        Some(split) => (&line[..split], line[split..].trim()),
+        None => (line, "")
+    };
+    let mnemonic = mnemonic.to_uppercase();
+
+    if mnemonic == "DCB" || mnemonic == ".BYTE" {
+        let values = operand.split(',')
+            .map(|value| parse_expression(value.trim(), defines))
+            .collect::<Result<Vec<Expression>, String>>()?;
+        return Ok(Statement::Bytes(values));
+    }
+
+    if !is_mnemonic(&mnemonic) {
+        return Err(format!("Unknown instruction [{}]", mnemonic));
+    }
+
+    let upper = operand.to_uppercase();
+
+    // No operand at all is either implied or the accumulator form of a shift
+    if operand.is_empty() || upper == "A" {
+        for mode in [AddressingMode::Implied, AddressingMode::Accumulator].iter() {
+            if let Some(opcode) = find_opcode(&mnemonic, *mode) {
+                return Ok(Statement::Instruction { opcode, mode: *mode, operand: None });
+            }
+        }
+        return Err(format!("{} needs an operand", mnemonic));
+    }
+
+    let (modes, inner): (Vec<AddressingMode>, &str) = if let Some(value) = operand.strip_prefix('#') {
+        (vec![AddressingMode::Immediate], value)
+    }
+    else if upper.starts_with('(') && upper.ends_with(",X)") {
+        (vec![AddressingMode::Indirect_X], &operand[1..operand.len() - 3])
+    }
+    else if upper.starts_with('(') && upper.ends_with("),Y") {
+        (vec![AddressingMode::Indirect_Y], &operand[1..operand.len() - 3])
+    }
+    else if upper.starts_with('(') && upper.ends_with(')') {
+        (vec![AddressingMode::Indirect], &operand[1..operand.len() - 1])
+    }
+    else if upper.ends_with(",X") {
+        (vec![AddressingMode::ZeroPage_X, AddressingMode::Absolute_X], &operand[..operand.len() - 2])
+    }
+    else if upper.ends_with(",Y") {
+        (vec![AddressingMode::ZeroPage_Y, AddressingMode::Absolute_Y], &operand[..operand.len() - 2])
+    }
+    else {
+        (vec![AddressingMode::Relative, AddressingMode::ZeroPage, AddressingMode::Absolute], operand)
+    };
+
+    let expression = parse_expression(inner.trim(), defines)?;
+
+    for mode in modes {
+        let is_zero_page = matches!(mode, AddressingMode::ZeroPage | AddressingMode::ZeroPage_X | AddressingMode::ZeroPage_Y);
+        if is_zero_page && !expression.is_byte {
+            continue;
+        }
+
+        if let Some(opcode) = find_opcode(&mnemonic, mode) {
+            return Ok(Statement::Instruction { opcode, mode, operand: Some(expression) });
+        }
+    }
+
+    Err(format!("{} can't be used with [{}]", mnemonic, operand))
+}
+
+fn parse_expression(text: &str, defines: &HashMap<String, String>) -> Result<Expression, String> {
+    let (selector, text) = if let Some(rest) = text.strip_prefix('<') {
+        (Selector::Low, rest)
+    }
+    else if let Some(rest) = text.strip_prefix('>') {
+        (Selector::High, rest)
+    }
+    else {
+        (Selector::Whole, text)
+    };
+
+    if let Some(value) = defines.get(text) {
+        let mut expression = parse_expression(value, defines)?;
+        if !matches!(selector, Selector::Whole) {
+            expression.selector = selector;
+            expression.is_byte = true;
+        }
+        return Ok(expression);
+    }
+
+    let parsed = if let Some(hex) = text.strip_prefix('$') {
+        u16::from_str_radix(hex, 16).map(|value| (value, hex.len() <= 2))
+    }
+    else if let Some(binary) = text.strip_prefix('%') {
+        u16::from_str_radix(binary, 2).map(|value| (value, binary.len() <= 8))
+    }
+    else if text.starts_with(|c: char| c.is_ascii_digit()) {
+        text.parse::<u16>().map(|value| (value, value <= 0xFF))
+    }
+    else if is_identifier(text) {
+        return Ok(Expression {
+            term: Term::Label(text.to_string()),
+            is_byte: !matches!(selector, Selector::Whole),
+            selector
+        });
+    }
+    else {
+        return Err(format!("Can't understand [{}]", text));
+    };
+
+    match parsed {
+        Ok((value, is_byte)) => Ok(Expression {
+            term: Term::Number(value),
+            is_byte: is_byte || !matches!(selector, Selector::Whole),
+            selector
+        }),
+        Err(_) => Err(format!("[{}] is not a valid number", text))
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() &&
+        text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') &&
+        text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_mnemonic(name: &str) -> bool {
+    (0..=0xFF).any(|opcode| map_instruction_to_name(opcode) == name)
+}
+
+// Official opcodes win when an unofficial one shares the same name and mode (NOP, SBC)
+fn find_opcode(name: &str, mode: AddressingMode) -> Option<u8> {
+    let matches = |opcode: &u8| map_instruction_to_name(*opcode) == name && map_instruction_to_addressing_mode(*opcode) == mode;
+
+    (0..=0xFFu8).filter(|opcode| !is_unofficial_instruction(*opcode)).find(matches)
+        .or_else(|| (0..=0xFFu8).find(matches))
+}
@@ -0,0 +1,225 @@
+use crate::audio::pulse::Pulse;
+use crate::audio::triangle::Triangle;
+use crate::audio::noise::Noise;
+use crate::audio::dmc::DMC;
+use crate::audio::mixer::mix;
+use crate::audio::resampler::Resampler;
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+pub const PULSE_1_START: u16  = 0x4000;
+pub const PULSE_2_START: u16  = 0x4004;
+pub const TRIANGLE_START: u16 = 0x4008;
+pub const NOISE_START: u16    = 0x400C;
+pub const DMC_START: u16      = 0x4010;
+pub const APU_STATUS: u16     = 0x4015;
+pub const FRAME_COUNTER: u16  = 0x4017;
+
+// $4015
+pub const STATUS_PULSE_1: u8   = 0b0000_0001;
+pub const STATUS_PULSE_2: u8   = 0b0000_0010;
+pub const STATUS_TRIANGLE: u8  = 0b0000_0100;
+pub const STATUS_NOISE: u8     = 0b0000_1000;
+pub const STATUS_DMC: u8       = 0b0001_0000;
+pub const STATUS_FRAME_IRQ: u8 = 0b0100_0000;
+pub const STATUS_DMC_IRQ: u8   = 0b1000_0000;
+
+// $4017
+pub const FRAME_COUNTER_FIVE_STEP: u8   = 0b1000_0000;
+pub const FRAME_COUNTER_IRQ_INHIBIT: u8 = 0b0100_0000;
+
+// CPU cycles into the frame sequence where the quarter and half frame clocks land,
+//  a little under 240Hz. The 5 step sequence has a gap where the 4 step one ends.
+const QUARTER_FRAME_STEP_1: u64 = 7457;
+const HALF_FRAME_STEP_2: u64    = 14913;
+const QUARTER_FRAME_STEP_3: u64 = 22371;
+const FOUR_STEP_LAST: u64       = 29829;
+const FIVE_STEP_LAST: u64       = 37281;
+const FOUR_STEP_LENGTH: u64     = 29830;
+const FIVE_STEP_LENGTH: u64     = 37282;
+
+// The audio half of the 2A03, mapped at $4000 - $4017.
+//  See https://www.nesdev.org/wiki/APU
+pub struct APU {
+    pub pulse_1: Pulse,
+    pub pulse_2: Pulse,
+    pub triangle: Triangle,
+    pub noise: Noise,
+    pub dmc: DMC,
+
+    // Swap this out for a different sample rate
+    pub resampler: Resampler,
+
+    // The pulse timers run off every other CPU cycle
+    cycles: u64,
+
+    // The frame counter, which clocks the envelopes, sweeps and length counters
+    //  See https://www.nesdev.org/wiki/APU_Frame_Counter
+    frame_cycle: u64,
+    five_step_mode: bool,
+    frame_irq_inhibit: bool,
+    frame_irq: bool
+}
+
+impl APU {
+    pub fn new() -> Self {
+        Self {
+            pulse_1: Pulse::new(true),
+            pulse_2: Pulse::new(false),
+            triangle: Triangle::new(),
+            noise: Noise::new(),
+            dmc: DMC::new(),
+            resampler: Resampler::new(DEFAULT_SAMPLE_RATE),
+            cycles: 0,
+            frame_cycle: 0,
+            five_step_mode: false,
+            frame_irq_inhibit: false,
+            frame_irq: false
+        }
+    }
+
+    // Reading $4015 acknowledges the frame IRQ, but not the DMC one
+    pub fn read_register(&mut self, addr: u16) -> u8 {
+        let data = self.peek_register(addr);
+        if addr == APU_STATUS {
+            self.frame_irq = false;
+        }
+        data
+    }
+
+    // Only $4015 can be read, everything else is write only
+    pub fn peek_register(&self, addr: u16) -> u8 {
+        if addr != APU_STATUS {
+            return 0;
+        }
+
+        let mut status = 0;
+        if self.pulse_1.length_counter.is_active() {
+            status |= STATUS_PULSE_1;
+        }
+        if self.pulse_2.length_counter.is_active() {
+            status |= STATUS_PULSE_2;
+        }
+        if self.triangle.length_counter.is_active() {
+            status |= STATUS_TRIANGLE;
+        }
+        if self.noise.length_counter.is_active() {
+            status |= STATUS_NOISE;
+        }
+        if self.dmc.bytes_remaining > 0 {
+            status |= STATUS_DMC;
+        }
+        if self.frame_irq {
+            status |= STATUS_FRAME_IRQ;
+        }
+        if self.dmc.irq {
+            status |= STATUS_DMC_IRQ;
+        }
+        status
+    }
+
+    pub fn write_register(&mut self, addr: u16, data: u8) {
+        match addr {
+            PULSE_1_START ..= 0x4003 => self.pulse_1.write_register(addr - PULSE_1_START, data),
+            PULSE_2_START ..= 0x4007 => self.pulse_2.write_register(addr - PULSE_2_START, data),
+            TRIANGLE_START ..= 0x400B => self.triangle.write_register(addr - TRIANGLE_START, data),
+            NOISE_START ..= 0x400F => self.noise.write_register(addr - NOISE_START, data),
+            DMC_START ..= 0x4013 => self.dmc.write_register(addr - DMC_START, data),
+            APU_STATUS => {
+                self.pulse_1.length_counter.set_enabled(data & STATUS_PULSE_1 != 0);
+                self.pulse_2.length_counter.set_enabled(data & STATUS_PULSE_2 != 0);
+                self.triangle.length_counter.set_enabled(data & STATUS_TRIANGLE != 0);
+                self.noise.length_counter.set_enabled(data & STATUS_NOISE != 0);
+                self.dmc.set_enabled(data & STATUS_DMC != 0);
+                self.dmc.irq = false;
+            },
+            FRAME_COUNTER => {
+                self.five_step_mode = data & FRAME_COUNTER_FIVE_STEP != 0;
+                self.frame_irq_inhibit = data & FRAME_COUNTER_IRQ_INHIBIT != 0;
+                if self.frame_irq_inhibit {
+                    self.frame_irq = false;
+                }
+
+                // Restarts the sequence, and switching to 5 step mode clocks everything straight away
+                self.frame_cycle = 0;
+                if self.five_step_mode {
+                    self.clock_quarter_frame();
+                    self.clock_half_frame();
+                }
+            },
+            _ => { }
+        }
+    }
+
+    // One CPU cycle
+    pub fn tick(&mut self) {
+        if self.cycles % 2 == 1 {
+            self.pulse_1.clock_timer();
+            self.pulse_2.clock_timer();
+        }
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        self.cycles += 1;
+
+        self.frame_cycle += 1;
+        match (self.frame_cycle, self.five_step_mode) {
+            (QUARTER_FRAME_STEP_1, _) | (QUARTER_FRAME_STEP_3, _) => self.clock_quarter_frame(),
+            (HALF_FRAME_STEP_2, _) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            },
+            (FOUR_STEP_LAST, false) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                if !self.frame_irq_inhibit {
+                    self.frame_irq = true;
+                }
+            },
+            (FIVE_STEP_LAST, true) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            },
+            _ => { }
+        }
+
+        let sequence_length = if self.five_step_mode { FIVE_STEP_LENGTH } else { FOUR_STEP_LENGTH };
+        if self.frame_cycle >= sequence_length {
+            self.frame_cycle = 0;
+        }
+
+        let output = self.output();
+        self.resampler.push(output);
+    }
+
+    // All the channels mixed together, 0.0 - 1.0
+    pub fn output(&self) -> f32 {
+        mix(self.pulse_1.output(), self.pulse_2.output(), self.triangle.output(), self.noise.output(), self.dmc.output())
+    }
+
+    // Everything played since the last call at the resampler's sample rate
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        self.resampler.take_samples()
+    }
+
+    // True while either the frame counter or the DMC is holding the IRQ line
+    pub fn irq(&self) -> bool {
+        self.frame_irq || self.dmc.irq
+    }
+
+    // Envelopes and the triangle's linear counter
+    fn clock_quarter_frame(&mut self) {
+        self.pulse_1.clock_quarter_frame();
+        self.pulse_2.clock_quarter_frame();
+        self.triangle.clock_quarter_frame();
+        self.noise.clock_quarter_frame();
+    }
+
+    // Length counters and sweeps
+    fn clock_half_frame(&mut self) {
+        self.pulse_1.clock_half_frame();
+        self.pulse_2.clock_half_frame();
+        self.triangle.clock_half_frame();
+        self.noise.clock_half_frame();
+    }
+}
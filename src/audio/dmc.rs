@@ -0,0 +1,151 @@
+// In CPU cycles
+const DMC_RATE_TABLE: [u16; 16] = [428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54];
+
+const SAMPLE_ADDRESS_START: u16 = 0xC000;
+
+// $4010 - $4013. Plays 1 bit delta encoded samples straight out of CPU memory, each bit 
+//  moves the output level up or down by 2. The bytes are fetched by the bus since the 
+//  CPU has to stop while that happens.
+//  See https://www.nesdev.org/wiki/APU_DMC
+pub struct DMC {
+    irq_enabled: bool,
+    looping: bool,
+    timer: u16,
+    timer_period: u16,
+
+    // 0 - 127
+    output_level: u8,
+
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    pub bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+
+    shift_register: u8,
+    bits_remaining: u8,
+    silence: bool,
+
+    pub irq: bool
+}
+
+impl DMC {
+    pub fn new() -> Self {
+        Self {
+            irq_enabled: false,
+            looping: false,
+            timer: 0,
+            timer_period: DMC_RATE_TABLE[0],
+            output_level: 0,
+            sample_address: SAMPLE_ADDRESS_START,
+            sample_length: 1,
+            current_address: SAMPLE_ADDRESS_START,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+            irq: false
+        }
+    }
+
+    // register is 0 - 3
+    pub fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.irq_enabled = data & 0x80 != 0;
+                if !self.irq_enabled {
+                    self.irq = false;
+                }
+                self.looping = data & 0x40 != 0;
+                self.timer_period = DMC_RATE_TABLE[(data & 0x0F) as usize];
+            },
+            1 => self.output_level = data & 0x7F,
+            // Samples start somewhere in $C000 - $FFC0 on a 64 byte boundary
+            2 => self.sample_address = SAMPLE_ADDRESS_START + (data as u16) * 64,
+            _ => self.sample_length = (data as u16) * 16 + 1
+        }
+    }
+
+    // Bit 4 of $4015
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.bytes_remaining = 0;
+        }
+        else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    // Where the next sample byte should come from, if the buffer needs filling
+    pub fn pending_read(&self) -> Option<u16> {
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_address)
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn load_sample(&mut self, data: u8) {
+        self.sample_buffer = Some(data);
+
+        // Runs off the end of memory back round to $8000
+        self.current_address = if self.current_address == 0xFFFF { 0x8000 } else { self.current_address + 1 };
+        self.bytes_remaining -= 1;
+
+        if self.bytes_remaining == 0 {
+            if self.looping {
+                self.restart();
+            }
+            else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    // Every CPU cycle, the rate table is already in CPU cycles
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        self.timer = self.timer_period - 1;
+
+        // The level stays put rather than wrapping when it would go out of range
+        if !self.silence {
+            if self.shift_register & 0x01 != 0 {
+                if self.output_level <= 125 {
+                    self.output_level += 2;
+                }
+            }
+            else if self.output_level >= 2 {
+                self.output_level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(sample) => {
+                    self.silence = false;
+                    self.shift_register = sample;
+                },
+                None => self.silence = true
+            }
+        }
+    }
+
+    // 0 - 127
+    pub fn output(&self) -> u8 {
+        self.output_level
+    }
+}
@@ -0,0 +1,13 @@
+// The channels are mixed through resistor networks on the real console, so they don't 
+//  just add together. These are the usual approximations of that, each input is the 
+//  channel's raw output level and the result is 0.0 - 1.0.
+//  See https://www.nesdev.org/wiki/APU_Mixer
+pub fn mix(pulse_1: u8, pulse_2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    let pulse_sum = (pulse_1 + pulse_2) as f32;
+    let pulse_out = if pulse_sum == 0.0 { 0.0 } else { 95.88 / (8128.0 / pulse_sum + 100.0) };
+
+    let tnd_sum = triangle as f32 / 8227.0 + noise as f32 / 12241.0 + dmc as f32 / 22638.0;
+    let tnd_out = if tnd_sum == 0.0 { 0.0 } else { 159.79 / (1.0 / tnd_sum + 100.0) };
+
+    pulse_out + tnd_out
+}
@@ -0,0 +1,83 @@
+use crate::audio::units::{Envelope, LengthCounter};
+
+// In CPU cycles
+const NOISE_PERIOD_TABLE: [u16; 16] = [4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068];
+
+// $400C - $400F. A 15 bit linear feedback shift register, in short mode the feedback 
+//  comes from bit 6 instead of bit 1 so the pattern repeats after 93 steps.
+//  See https://www.nesdev.org/wiki/APU_Noise
+pub struct Noise {
+    shift_register: u16,
+    short_mode: bool,
+    timer: u16,
+    timer_period: u16,
+
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter
+}
+
+impl Noise {
+    pub fn new() -> Self {
+        Self {
+            // Loaded with 1 at power on, all zeroes would never change
+            shift_register: 1,
+            short_mode: false,
+            timer: 0,
+            timer_period: NOISE_PERIOD_TABLE[0],
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new()
+        }
+    }
+
+    // register is 0 - 3, $400D doesn't do anything
+    pub fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.length_counter.halted = data & 0x20 != 0;
+                self.envelope.looping = data & 0x20 != 0;
+                self.envelope.constant_volume = data & 0x10 != 0;
+                self.envelope.volume = data & 0x0F;
+            },
+            1 => { },
+            2 => {
+                self.short_mode = data & 0x80 != 0;
+                self.timer_period = NOISE_PERIOD_TABLE[(data & 0x0F) as usize];
+            },
+            _ => {
+                self.length_counter.load(data >> 3);
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    // Every CPU cycle, the period table is already in CPU cycles
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        self.timer = self.timer_period - 1;
+
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 0x01;
+        self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    // 0 - 15
+    pub fn output(&self) -> u8 {
+        if self.shift_register & 0x01 != 0 || !self.length_counter.is_active() {
+            return 0;
+        }
+
+        self.envelope.output()
+    }
+}
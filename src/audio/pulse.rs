@@ -0,0 +1,143 @@
+use crate::audio::units::{Envelope, LengthCounter};
+
+// Which 8 step pattern the channel plays, 12.5%, 25%, 50% and 25% negated
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1]
+];
+
+// Periods past this are out of range for the timer, the sweep mutes the channel instead
+const MAX_PERIOD: u16 = 0x7FF;
+
+// Anything under 8 is too high to hear and the real chip mutes it too
+const MIN_PERIOD: u16 = 8;
+
+// The two square wave channels, $4000 - $4003 and $4004 - $4007.
+//  See https://www.nesdev.org/wiki/APU_Pulse
+pub struct Pulse {
+    // Pulse 1 negates with ones' complement, so it sweeps down one further than pulse 2
+    ones_complement: bool,
+
+    duty: u8,
+    sequence_step: usize,
+    timer: u16,
+    pub timer_period: u16,
+
+    sweep_enabled: bool,
+    sweep_period: u8,
+    sweep_negate: bool,
+    sweep_shift: u8,
+    sweep_divider: u8,
+    sweep_reload: bool,
+
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter
+}
+
+impl Pulse {
+    pub fn new(ones_complement: bool) -> Self {
+        Self {
+            ones_complement,
+            duty: 0,
+            sequence_step: 0,
+            timer: 0,
+            timer_period: 0,
+            sweep_enabled: false,
+            sweep_period: 0,
+            sweep_negate: false,
+            sweep_shift: 0,
+            sweep_divider: 0,
+            sweep_reload: false,
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new()
+        }
+    }
+
+    // register is 0 - 3, the offset from the channel's first register
+    pub fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.duty = data >> 6;
+                self.length_counter.halted = data & 0x20 != 0;
+                self.envelope.looping = data & 0x20 != 0;
+                self.envelope.constant_volume = data & 0x10 != 0;
+                self.envelope.volume = data & 0x0F;
+            },
+            1 => {
+                self.sweep_enabled = data & 0x80 != 0;
+                self.sweep_period = (data >> 4) & 0x07;
+                self.sweep_negate = data & 0x08 != 0;
+                self.sweep_shift = data & 0x07;
+                self.sweep_reload = true;
+            },
+            2 => self.timer_period = (self.timer_period & 0x0700) | data as u16,
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length_counter.load(data >> 3);
+                self.sequence_step = 0;
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    // Every APU cycle, which is every other CPU cycle
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence_step = (self.sequence_step + 1) % 8;
+        }
+        else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+        self.clock_sweep();
+    }
+
+    fn clock_sweep(&mut self) {
+        if self.sweep_divider == 0 && self.sweep_enabled && self.sweep_shift != 0 && !self.is_sweep_muting() {
+            self.timer_period = self.sweep_target();
+        }
+
+        if self.sweep_divider == 0 || self.sweep_reload {
+            self.sweep_divider = self.sweep_period;
+            self.sweep_reload = false;
+        }
+        else {
+            self.sweep_divider -= 1;
+        }
+    }
+
+    // The period the sweep would move to next, worked out all the time even when the 
+    //  sweep is off since it can still mute the channel
+    fn sweep_target(&self) -> u16 {
+        let change = self.timer_period >> self.sweep_shift;
+        if !self.sweep_negate {
+            return self.timer_period + change;
+        }
+
+        let change = if self.ones_complement { change + 1 } else { change };
+        self.timer_period.saturating_sub(change)
+    }
+
+    fn is_sweep_muting(&self) -> bool {
+        self.timer_period < MIN_PERIOD || self.sweep_target() > MAX_PERIOD
+    }
+
+    // 0 - 15
+    pub fn output(&self) -> u8 {
+        if DUTY_TABLE[self.duty as usize][self.sequence_step] == 0 || !self.length_counter.is_active() || self.is_sweep_muting() {
+            return 0;
+        }
+
+        self.envelope.output()
+    }
+}
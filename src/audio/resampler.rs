@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+
+// NTSC CPU clock, the APU puts out one sample every cycle
+pub const CPU_CLOCK_RATE: f64 = 1_789_773.0;
+
+// What the console's own output circuit does to the signal, two high passes that take
+//  out the DC offset and a low pass that rounds the edges off
+const HIGH_PASS_1_HZ: f32 = 90.0;
+const HIGH_PASS_2_HZ: f32 = 440.0;
+const LOW_PASS_HZ: f32    = 14000.0;
+
+// Anything above this fraction of the output rate gets taken out before decimating, 
+//  leaving a bit of room below Nyquist for the filter to roll off in
+const ANTI_ALIAS_CUTOFF: f64 = 0.4;
+// Q of each section of an 8th order Butterworth, 1 / (2 cos((2k - 1) * pi / 16))
+const BUTTERWORTH_Q: [f64; 4] = [0.5097955791, 0.6013448869, 0.8999762231, 2.5629154478];
+
+// First order RC filter
+struct Filter {
+    high_pass: bool,
+    alpha: f32,
+    previous_input: f32,
+    previous_output: f32
+}
+
+impl Filter {
+    fn high_pass(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+
+        Self {
+            high_pass: true,
+            alpha: rc / (rc + dt),
+            previous_input: 0.0,
+            previous_output: 0.0
+        }
+    }
+
+    fn low_pass(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+
+        Self {
+            high_pass: false,
+            alpha: dt / (rc + dt),
+            previous_input: 0.0,
+            previous_output: 0.0
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = if self.high_pass {
+            self.alpha * (self.previous_output + input - self.previous_input)
+        }
+        else {
+            self.previous_output + self.alpha * (input - self.previous_output)
+        };
+
+        self.previous_input = input;
+        self.previous_output = output;
+        output
+    }
+}
+
+// Second order low pass, from the RBJ audio EQ cookbook. This runs at the CPU rate where
+//  the cutoff is tiny compared to the sample rate, so it needs the f64s.
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    // Transposed direct form II state
+    z1: f64,
+    z2: f64
+}
+
+impl Biquad {
+    fn low_pass(cutoff: f64, q: f64, sample_rate: f64) -> Self {
+        let w0 = 2.0 * std::f64::consts::PI * cutoff / sample_rate;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 - cos_w0) / 2.0 / a0,
+            b1: (1.0 - cos_w0) / a0,
+            b2: (1.0 - cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+}
+
+// Brings the ~1.79MHz APU output down to something a sound card (or a WAV file) can 
+//  take. The console's low pass and a steep anti-aliasing filter run on every APU 
+//  sample, then every output sample is the average of the filtered APU samples in its 
+//  window. The high passes don't care about aliasing so they run at the output rate.
+pub struct Resampler {
+    cycles_per_sample: f64,
+
+    console_low_pass: Filter,
+    anti_alias: [Biquad; 4],
+
+    sum: f64,
+    count: u32,
+    // How far through the current output sample we are, in CPU cycles
+    position: f64,
+
+    filters: [Filter; 2],
+    samples: Vec<f32>
+}
+
+impl Resampler {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            cycles_per_sample: CPU_CLOCK_RATE / sample_rate as f64,
+            console_low_pass: Filter::low_pass(LOW_PASS_HZ, CPU_CLOCK_RATE as u32),
+            anti_alias: [
+                Biquad::low_pass(sample_rate as f64 * ANTI_ALIAS_CUTOFF, BUTTERWORTH_Q[0], CPU_CLOCK_RATE),
+                Biquad::low_pass(sample_rate as f64 * ANTI_ALIAS_CUTOFF, BUTTERWORTH_Q[1], CPU_CLOCK_RATE),
+                Biquad::low_pass(sample_rate as f64 * ANTI_ALIAS_CUTOFF, BUTTERWORTH_Q[2], CPU_CLOCK_RATE),
+                Biquad::low_pass(sample_rate as f64 * ANTI_ALIAS_CUTOFF, BUTTERWORTH_Q[3], CPU_CLOCK_RATE)
+            ],
+            sum: 0.0,
+            count: 0,
+            position: 0.0,
+            filters: [
+                Filter::high_pass(HIGH_PASS_1_HZ, sample_rate),
+                Filter::high_pass(HIGH_PASS_2_HZ, sample_rate)
+            ],
+            samples: Vec::new()
+        }
+    }
+
+    // One CPU cycle's worth of output
+    pub fn push(&mut self, sample: f32) {
+        let mut sample = self.console_low_pass.process(sample) as f64;
+        for filter in self.anti_alias.iter_mut() {
+            sample = filter.process(sample);
+        }
+
+        self.sum += sample;
+        self.count += 1;
+        self.position += 1.0;
+
+        if self.position < self.cycles_per_sample {
+            return;
+        }
+
+        // The leftover fraction carries over so the rate works out exactly over time
+        self.position -= self.cycles_per_sample;
+
+        let mut output = (self.sum / self.count as f64) as f32;
+        for filter in self.filters.iter_mut() {
+            output = filter.process(output);
+        }
+        self.samples.push(output);
+
+        self.sum = 0.0;
+        self.count = 0;
+    }
+
+    // Everything resampled since the last call, roughly -1.0 - 1.0
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::audio::units::LengthCounter;
+
+// Steps down from 15 to 0 and back up again
+const TRIANGLE_SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10,  9,  8,  7,  6,  5,  4,  3,  2,  1,  0,
+     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15
+];
+
+// $4008 - $400B. No volume control, but it has a second, finer grained length counter.
+//  See https://www.nesdev.org/wiki/APU_Triangle
+pub struct Triangle {
+    sequence_step: usize,
+    timer: u16,
+    pub timer_period: u16,
+
+    // The same bit halts the length counter and stops the linear counter reload flag
+    //  from being cleared
+    control: bool,
+    linear_counter: u8,
+    linear_counter_period: u8,
+    linear_counter_reload: bool,
+
+    pub length_counter: LengthCounter
+}
+
+impl Triangle {
+    pub fn new() -> Self {
+        Self {
+            sequence_step: 0,
+            timer: 0,
+            timer_period: 0,
+            control: false,
+            linear_counter: 0,
+            linear_counter_period: 0,
+            linear_counter_reload: false,
+            length_counter: LengthCounter::new()
+        }
+    }
+
+    // register is 0 - 3, $4009 doesn't do anything
+    pub fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.control = data & 0x80 != 0;
+                self.length_counter.halted = self.control;
+                self.linear_counter_period = data & 0x7F;
+            },
+            1 => { },
+            2 => self.timer_period = (self.timer_period & 0x0700) | data as u16,
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length_counter.load(data >> 3);
+                self.linear_counter_reload = true;
+            }
+        }
+    }
+
+    // Every CPU cycle, unlike the other channels
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        // Periods under 2 are too high to hear and only make popping noises when mixed,
+        //  so the sequencer holds still for those instead
+        self.timer = self.timer_period;
+        if self.linear_counter > 0 && self.length_counter.is_active() && self.timer_period >= 2 {
+            self.sequence_step = (self.sequence_step + 1) % TRIANGLE_SEQUENCE.len();
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        if self.linear_counter_reload {
+            self.linear_counter = self.linear_counter_period;
+        }
+        else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+
+        if !self.control {
+            self.linear_counter_reload = false;
+        }
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    // 0 - 15. Stopping the sequencer leaves the output wherever it was rather than 
+    //  dropping to 0.
+    pub fn output(&self) -> u8 {
+        TRIANGLE_SEQUENCE[self.sequence_step]
+    }
+}
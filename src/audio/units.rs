@@ -0,0 +1,102 @@
+// Pieces that more than one channel is built out of
+
+// How many half frames a channel plays for, indexed by the top 5 bits of the 
+//  length register
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20,  2, 40,  4, 80,  6, 160,  8, 60, 10, 14, 12, 26, 14,
+    12,  16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30
+];
+
+// Silences the channel once it counts down to zero, unless it is halted
+pub struct LengthCounter {
+    pub enabled: bool,
+    pub halted: bool,
+    counter: u8
+}
+
+impl LengthCounter {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            halted: false,
+            counter: 0
+        }
+    }
+
+    // Loads are ignored while the channel is turned off in $4015
+    pub fn load(&mut self, index: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(index & 0x1F) as usize];
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    // Half frame
+    pub fn clock(&mut self) {
+        if self.counter > 0 && !self.halted {
+            self.counter -= 1;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.counter > 0
+    }
+}
+
+// Either a constant volume, or a sawtooth that goes from 15 down to 0 (and optionally 
+//  loops) at a rate set by the same 4 bits
+pub struct Envelope {
+    pub start: bool,
+    pub looping: bool,
+    pub constant_volume: bool,
+    // Both the constant volume and the divider period
+    pub volume: u8,
+    divider: u8,
+    decay: u8
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Self {
+            start: false,
+            looping: false,
+            constant_volume: false,
+            volume: 0,
+            divider: 0,
+            decay: 0
+        }
+    }
+
+    // Quarter frame
+    pub fn clock(&mut self) {
+        if self.start {
+            self.start = false;
+            self.decay = 15;
+            self.divider = self.volume;
+            return;
+        }
+
+        if self.divider > 0 {
+            self.divider -= 1;
+            return;
+        }
+
+        self.divider = self.volume;
+        if self.decay > 0 {
+            self.decay -= 1;
+        }
+        else if self.looping {
+            self.decay = 15;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.constant_volume { self.volume } else { self.decay }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::audio::apu::*;
+use crate::controller::Controller;
+use crate::file_format::*;
+use crate::mappers::mapper::{Cartridge, create_mapper};
+use crate::mappers::nrom::{PROGRAM_RAM_START, PROGRAM_ROM_START};
+use crate::ppu::PPU;
+use crate::ppu::OAMDATA;
+
+pub const RAM_SIZE: usize             = 0x0800;
+pub const RAM_MIRROR_END: u16         = 0x1FFF;
+pub const PPU_REGISTERS_START: u16    = 0x2000;
+pub const PPU_REGISTERS_END: u16      = 0x3FFF;
+pub const APU_IO_REGISTERS_START: u16 = 0x4000;
+pub const APU_IO_REGISTERS_END: u16   = 0x401F;
+pub const OAM_DMA: u16                = 0x4014;
+pub const JOYPAD_1: u16               = 0x4016;
+pub const JOYPAD_2: u16               = 0x4017;
+pub const CARTRIDGE_SPACE_START: u16  = 0x4020;
+
+// OAM DMA takes one cycle to get going (two if it has to wait for an even cycle), then
+//  a read and a write for each of the 256 bytes
+pub const OAM_DMA_CYCLES: u16         = 513;
+
+// The CPU gets held up for a few cycles each time the DMC fetches a sample byte
+pub const DMC_DMA_CYCLES: u16         = 4;
+
+// Everything the CPU talks to goes through here.
+pub trait Bus {
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    // Reads without any of the side effects a real read would have (clearing flags, 
+    //  advancing buffers), for debugging tools that need to look at memory. 
+    fn peek(&self, addr: u16) -> u8;
+
+    // The scanline and dot the PPU is on, if there is one attached
+    fn ppu_position(&self) -> Option<(u16, u16)> {
+        None
+    }
+
+    // Lets everything else on the bus catch up after the CPU has used some cycles
+    fn tick(&mut self, _cycles: u16) {
+    }
+
+    // How many cycles the CPU has to sit out for DMA started by the last instruction
+    fn take_stall_cycles(&mut self, _odd_cycle: bool) -> u16 {
+        0
+    }
+
+    // True once for every NMI something on the bus has raised
+    fn poll_nmi(&mut self) -> bool {
+        false
+    }
+
+    // True while something on the bus is holding the IRQ line
+    fn irq(&self) -> bool {
+        false
+    }
+}
+
+// A plain 64K of RAM with nothing mapped into it. This is what the unit tests and the
+//  integration test programs (which were written for a generic 6502) expect.
+pub struct FlatBus {
+    memory: Vec<u8>
+}
+
+impl FlatBus {
+    pub fn new() -> Self {
+        Self {
+            memory: vec![0; 0x10000]
+        }
+    }
+}
+
+impl Bus for FlatBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.memory[addr as usize] = data;
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+}
+
+// The CPU side of the NES memory map
+//  $0000 - $07FF : 2KB internal RAM, mirrored up to $1FFF
+//  $2000 - $2007 : PPU registers, mirrored every 8 bytes up to $3FFF
+//  $4000 - $401F : APU and I/O registers
+//  $4020 - $FFFF : Cartridge space
+pub struct NESBus {
+    ram: [u8; RAM_SIZE],
+    pub ppu: PPU,
+    pub apu: APU,
+    pub controllers: [Controller; 2],
+
+    // The APU test registers, these just latch whatever gets written
+    apu_io_registers: [u8; 0x20],
+
+    // Whatever was last read or written. Anything that doesn't drive all 8 bits (like 
+    //  the controller ports) leaves the rest of this behind.
+    data_bus: u8,
+
+    // Cartridge space is plain RAM until a cartridge gets plugged in, which is 
+    //  what load_program writes to
+    cartridge: Option<Cartridge>,
+    cartridge_space: Vec<u8>,
+
+    // Set by any write to $6000 - $7FFF, so the battery save only gets written out 
+    //  when the game has actually changed something
+    pub program_ram_written: bool,
+
+    oam_dma_pending: bool,
+    dmc_stall_cycles: u16
+}
+
+impl NESBus {
+    pub fn new() -> Self {
+        Self {
+            ram: [0; RAM_SIZE],
+            ppu: PPU::new(),
+            apu: APU::new(),
+            controllers: [Controller::new(), Controller::new()],
+            apu_io_registers: [0; 0x20],
+            data_bus: 0,
+            cartridge: None,
+            cartridge_space: vec![0; 0x10000 - CARTRIDGE_SPACE_START as usize],
+            program_ram_written: false,
+            oam_dma_pending: false,
+            dmc_stall_cycles: 0
+        }
+    }
+
+    // Copies a program straight into cartridge space, for when there is no cartridge
+    #[cfg(test)]
+    pub fn load_program(&mut self, addr: u16, program: &[u8]) {
+        assert!(addr >= CARTRIDGE_SPACE_START, "Programs can only be loaded into cartridge space. ");
+
+        let start = (addr - CARTRIDGE_SPACE_START) as usize;
+        self.cartridge_space[start..start + program.len()].copy_from_slice(program);
+    }
+
+    // Puts a cartridge in, both the CPU and the PPU get wired up to it
+    pub fn load_rom(&mut self, rom: &INES) -> Result<(), RomError> {
+        let cartridge = create_mapper(rom)?;
+
+        if let Some(trainer) = &rom.trainer {
+            let mut cartridge = cartridge.borrow_mut();
+            for (offset, data) in trainer.iter().enumerate() {
+                cartridge.cpu_write(TRAINER_ADDRESS + offset as u16, *data);
+            }
+        }
+
+        self.ppu.cartridge = Some(cartridge.clone());
+        self.cartridge = Some(cartridge);
+        Ok(())
+    }
+
+    pub fn cartridge(&self) -> Option<&Cartridge> {
+        self.cartridge.as_ref()
+    }
+
+    // Copies $XX00 - $XXFF into OAM through OAMDATA, so it starts wherever OAMADDR is
+    fn oam_dma(&mut self, page: u8) {
+        let start = (page as u16) << 8;
+        for offset in 0..0x100 {
+            let data = self.read(start + offset);
+            self.ppu.write_register(OAMDATA, data);
+        }
+
+        self.oam_dma_pending = true;
+    }
+}
+
+impl Bus for NESBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        let data = match addr {
+            PPU_REGISTERS_START ..= PPU_REGISTERS_END => self.ppu.read_register(addr),
+            APU_STATUS => self.apu.read_register(addr),
+            JOYPAD_1 => (self.data_bus & 0xE0) | self.controllers[0].read(),
+            JOYPAD_2 => (self.data_bus & 0xE0) | self.controllers[1].read(),
+            CARTRIDGE_SPACE_START ..= 0xFFFF if self.cartridge.is_some() => {
+                self.cartridge.as_ref().unwrap().borrow_mut().cpu_read(addr)
+            },
+            _ => self.peek(addr)
+        };
+
+        self.data_bus = data;
+        data
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000 ..= RAM_MIRROR_END => self.ram[addr as usize & (RAM_SIZE - 1)],
+            PPU_REGISTERS_START ..= PPU_REGISTERS_END => self.ppu.peek_register(addr),
+            PULSE_1_START ..= 0x4013 | APU_STATUS => self.apu.peek_register(addr),
+            // The controllers only drive the bottom bits, the rest is open bus. Normally
+            //  that is $40, the high byte of the address in LDA $4016.
+            JOYPAD_1 => (self.data_bus & 0xE0) | self.controllers[0].peek(),
+            JOYPAD_2 => (self.data_bus & 0xE0) | self.controllers[1].peek(),
+            APU_IO_REGISTERS_START ..= APU_IO_REGISTERS_END => self.apu_io_registers[(addr - APU_IO_REGISTERS_START) as usize],
+            _ => match &self.cartridge {
+                Some(cartridge) => cartridge.borrow().cpu_peek(addr),
+                None => self.cartridge_space[(addr - CARTRIDGE_SPACE_START) as usize]
+            }
+        }
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.data_bus = data;
+
+        match addr {
+            0x0000 ..= RAM_MIRROR_END => self.ram[addr as usize & (RAM_SIZE - 1)] = data,
+            PPU_REGISTERS_START ..= PPU_REGISTERS_END => self.ppu.write_register(addr, data),
+            OAM_DMA => self.oam_dma(data),
+            // Strobes both controllers, $4017 is the APU frame counter when written
+            JOYPAD_1 => {
+                self.controllers[0].write(data);
+                self.controllers[1].write(data);
+            },
+            PULSE_1_START ..= 0x4013 | APU_STATUS | FRAME_COUNTER => self.apu.write_register(addr, data),
+            APU_IO_REGISTERS_START ..= APU_IO_REGISTERS_END => self.apu_io_registers[(addr - APU_IO_REGISTERS_START) as usize] = data,
+            _ => match &self.cartridge {
+                Some(cartridge) => {
+                    if (PROGRAM_RAM_START .. PROGRAM_ROM_START).contains(&addr) {
+                        self.program_ram_written = true;
+                    }
+                    cartridge.borrow_mut().cpu_write(addr, data);
+                },
+                None => self.cartridge_space[(addr - CARTRIDGE_SPACE_START) as usize] = data
+            }
+        }
+    }
+
+    fn ppu_position(&self) -> Option<(u16, u16)> {
+        Some((self.ppu.scanline, self.ppu.dot))
+    }
+
+    // The PPU runs three dots for every CPU cycle
+    fn tick(&mut self, cycles: u16) {
+        for _ in 0..cycles {
+            self.apu.tick();
+
+            if let Some(cartridge) = &self.cartridge {
+                cartridge.borrow_mut().cpu_cycle();
+            }
+
+            if let Some(addr) = self.apu.dmc.pending_read() {
+                let data = self.read(addr);
+                self.apu.dmc.load_sample(data);
+                self.dmc_stall_cycles += DMC_DMA_CYCLES;
+            }
+        }
+
+        for _ in 0..(cycles * 3) {
+            self.ppu.tick();
+        }
+    }
+
+    fn take_stall_cycles(&mut self, odd_cycle: bool) -> u16 {
+        let mut stall_cycles = self.dmc_stall_cycles;
+        self.dmc_stall_cycles = 0;
+
+        if self.oam_dma_pending {
+            self.oam_dma_pending = false;
+            stall_cycles += OAM_DMA_CYCLES + if odd_cycle { 1 } else { 0 };
+        }
+
+        stall_cycles
+    }
+
+    fn poll_nmi(&mut self) -> bool {
+        self.ppu.poll_nmi()
+    }
+
+    fn irq(&self) -> bool {
+        self.apu.irq() || self.cartridge.as_ref().is_some_and(|cartridge| cartridge.borrow().irq())
+    }
+}
@@ -0,0 +1,63 @@
+// Bits in the order the controller shifts them out. Only the window front-end presses
+//  buttons, a headless run leaves both controllers idle.
+#[cfg_attr(not(feature = "window"), allow(dead_code))]
+pub mod buttons {
+    pub const BUTTON_A: u8      = 0b0000_0001;
+    pub const BUTTON_B: u8      = 0b0000_0010;
+    pub const BUTTON_SELECT: u8 = 0b0000_0100;
+    pub const BUTTON_START: u8  = 0b0000_1000;
+    pub const BUTTON_UP: u8     = 0b0001_0000;
+    pub const BUTTON_DOWN: u8   = 0b0010_0000;
+    pub const BUTTON_LEFT: u8   = 0b0100_0000;
+    pub const BUTTON_RIGHT: u8  = 0b1000_0000;
+}
+
+// A standard joypad. Writing 1 to $4016 holds the strobe high, which keeps loading the 
+//  buttons into a shift register, then writing 0 latches them and each read gets the 
+//  next one. After all 8 have been read it just gives back 1s.
+//  See https://www.nesdev.org/wiki/Standard_controller
+pub struct Controller {
+    buttons: u8,
+    shift_register: u8,
+    strobe: bool
+}
+
+impl Controller {
+    pub fn new() -> Self {
+        Self {
+            buttons: 0,
+            shift_register: 0,
+            strobe: false
+        }
+    }
+
+    // Whichever buttons are held down right now, see the buttons module
+    #[cfg_attr(not(feature = "window"), allow(dead_code))]
+    pub fn set_buttons(&mut self, buttons: u8) {
+        self.buttons = buttons;
+        if self.strobe {
+            self.shift_register = buttons;
+        }
+    }
+
+    // Only bit 0 is connected
+    pub fn write(&mut self, data: u8) {
+        self.strobe = data & 0x01 != 0;
+        if self.strobe {
+            self.shift_register = self.buttons;
+        }
+    }
+
+    // Just the button in bit 0, the rest of the byte is up to whoever is reading
+    pub fn read(&mut self) -> u8 {
+        let bit = self.peek();
+        if !self.strobe {
+            self.shift_register = (self.shift_register >> 1) | 0x80;
+        }
+        bit
+    }
+
+    pub fn peek(&self) -> u8 {
+        if self.strobe { self.buttons & 0x01 } else { self.shift_register & 0x01 }
+    }
+}
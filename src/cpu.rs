@@ -1,6 +1,7 @@
 pub const NEGATIVE: u8          = 0b1000_0000;
 pub const OVERFLOW: u8          = 0b0100_0000;
-pub const BREAK:    u8          = 0b0010_0000;
+pub const UNUSED:   u8          = 0b0010_0000;
+pub const BREAK:    u8          = 0b0001_0000;
 
 pub const DECIMAL_MODE: u8      = 0b0000_1000;
 pub const INTERRUPT_DISABLE: u8 = 0b0000_0100;
@@ -11,18 +12,58 @@ pub const SIGN_BIT: u8          = 0b1000_0000;
 
 pub const PROGRAM_START_ADDR:  u16 = 0x8000;
 pub const PROGRAM_READ_START:  u16 = 0xFFFC;
+pub const NMI_VECTOR:          u16 = 0xFFFA;
+pub const IRQ_VECTOR:          u16 = 0xFFFE;
 pub const STACK_START_ADDR:    u16 = 0x0100;
 pub const INITIAL_STACK_VALUE: u8  = 0xFF;
 
+pub const INTERRUPT_CYCLES: u16    = 7;
+
+// XAA and LXA OR this into A before the AND, real chips use anything from $00 to $FF
+//  but $EE is the most common.
+pub const UNSTABLE_MAGIC: u8 = 0xEE;
+
+// What to do when the CPU runs into one of the unofficial opcodes
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum IllegalOpcodeMode {
+    Execute,
+    Log,
+    Trap
+}
+
 use crate::addressing_modes::*;
 use crate::util::*;
+use crate::bus::*;
+use crate::trace::TraceLogger;
 
-pub struct CPU {
+pub struct CPU<B: Bus = FlatBus> {
     pub program_counter: u16,
     pub registers: Registers,
     pub stack_pointer: u8,
     pub status: u8,
-    pub memory: [u8; 0xFFFF]
+    pub bus: B,
+
+    // NMI is edge triggered so it gets latched until serviced, IRQ is level 
+    //  triggered and stays asserted for as long as the source holds it. 
+    pub nmi_pending: bool,
+    pub irq_line: bool,
+
+    // Total number of CPU cycles run since power on
+    pub cycles: u64,
+
+    // Set by the addressing mode when an indexed address lands on a different page
+    //  than its base, and by branches when they are taken. Both cost extra cycles. 
+    pub page_crossed: bool,
+    extra_cycles: u8,
+
+    pub illegal_opcode_mode: IllegalOpcodeMode,
+
+    // Set by KIL, or by an illegal opcode when trapping on them. Only a reset gets 
+    //  the CPU going again. 
+    pub halted: bool,
+
+    // When set, every instruction gets logged before it runs
+    pub trace: Option<TraceLogger>
 }
 
 pub struct Registers {
@@ -31,13 +72,32 @@ pub struct Registers {
     pub y: u8,
 }
 
-impl CPU 
+impl CPU<FlatBus>
 {
     pub fn new(program: Vec<u8>) -> Self {
 
-        let mut memory: [u8; 0xFFFF] = [0; 0xFFFF];
-        memory[PROGRAM_START_ADDR as usize .. (PROGRAM_START_ADDR as usize + program.len())].copy_from_slice(&program[..]);
+        let mut cpu = CPU::with_bus(FlatBus::new());
+        for (offset, byte) in program.iter().enumerate() {
+            cpu.write(PROGRAM_START_ADDR + offset as u16, *byte);
+        }
+
+        cpu.write16(PROGRAM_READ_START, PROGRAM_START_ADDR);
+        cpu.reset();
+
+        // Test programs start from a clean slate instead of the power on state, it 
+        //  makes checking flags and the stack a lot simpler. 
+        cpu.status = 0;
+        cpu.stack_pointer = INITIAL_STACK_VALUE;
+        cpu.cycles = 0;
+        cpu
+    }
+}
 
+impl<B: Bus> CPU<B>
+{
+    // The program counter gets loaded from the reset vector, so whatever is on the 
+    //  bus needs to have it set up before this is called. 
+    pub fn with_bus(bus: B) -> Self {
         let mut cpu = CPU {
             program_counter: PROGRAM_START_ADDR,
             registers: Registers {
@@ -45,12 +105,19 @@ impl CPU
                 x: 0,
                 y: 0,
             },
-            stack_pointer: INITIAL_STACK_VALUE,
+            stack_pointer: 0,
             status: 0,
-            memory: memory
+            bus: bus,
+            nmi_pending: false,
+            irq_line: false,
+            cycles: 0,
+            page_crossed: false,
+            extra_cycles: 0,
+            illegal_opcode_mode: IllegalOpcodeMode::Execute,
+            halted: false,
+            trace: None
         };
 
-        cpu.write16(PROGRAM_READ_START, PROGRAM_START_ADDR);
         cpu.reset();
         cpu
     }
@@ -64,9 +131,11 @@ impl CPU
         println!(" Y: {:?}", self.registers.y);
         println!(" Status: 0x{:08b}", self.status);
         println!(" Program Counter: {:?} aka 0x{:04x}", self.program_counter, self.program_counter);
+        let top_of_stack = self.peep_stack();
+        let next_instruction = self.load(self.program_counter);
         println!(" Stack is at location {:?} w/value on top of {:?}", 
-                self.stack_pointer, self.memory[(STACK_START_ADDR | self.stack_pointer as u16) as usize]);
-        println!(" Next Instruction: 0x{:02x}", self.memory[self.program_counter as usize]);
+                self.stack_pointer, top_of_stack);
+        println!(" Next Instruction: 0x{:02x}", next_instruction);
     }
 
     #[allow(dead_code)]
@@ -84,12 +153,13 @@ impl CPU
     pub fn dump_memory(&mut self, start: u16, end: u16) {
         let display_width: u32 = 16;
         let mut line = start as u32;
-        for byte in self.memory[start as usize..end as usize].iter() {
+        for addr in start..end {
+            let byte = self.load(addr);
             if line % display_width == 0  || line == 0 {
                 print!("0x{:04x} ", line);
             }
 
-            if *byte == 0 {
+            if byte == 0 {
                 print!(" .. ");
             }
             else {
@@ -120,12 +190,13 @@ impl CPU
         }
         let mut file_out = BufWriter::new(file.unwrap());   
 
-        for byte in self.memory[start as usize..end as usize].iter() {
+        for addr in start..end {
+            let byte = self.load(addr);
             if line % display_width == 0  || line == 0 {
                 file_out.write(format!("0x{:04x} ", line).as_bytes()).expect("Failed to format memdump line number");
             }
 
-            if *byte == 0 {
+            if byte == 0 {
                 file_out.write(format!(" .. ").as_bytes()).expect("Failed to format memdump '..'");
             }
             else {
@@ -140,18 +211,26 @@ impl CPU
         file_out.flush().expect("Failed to flush memdump");
     }
 
+    // Reset goes through the same sequence as an interrupt except the pushes turn into 
+    //  reads, so the stack pointer still moves down by three and it takes seven cycles. 
+    //  From power on that leaves the stack pointer at $FD. 
     pub fn reset(&mut self) {
         self.registers.a = 0;
         self.registers.x = 0;
         self.registers.y = 0;
-        self.status = 0;
+        self.stack_pointer = self.stack_pointer.wrapping_sub(3);
+        self.set_interrupt_disable_bit();
+        self.halted = false;
+        self.add_cycles(INTERRUPT_CYCLES);
 
         self.program_counter = self.load16(PROGRAM_READ_START);
     }
 
     pub fn run_program(&mut self) {
-        loop {
-            if self.memory[self.program_counter as usize] == 0x00 {
+        while !self.halted {
+            // A BRK with nothing installed at the IRQ/BRK vector has nowhere to go, 
+            //  so the test programs use it to mark the end of the program. 
+            if self.load(self.program_counter) == 0x00 && self.load16(IRQ_VECTOR) == 0x0000 {
                 break;
             }
             self.run_next_instruction();
@@ -163,16 +242,65 @@ impl CPU
         }
     }
 
-    pub fn run_next_instruction(&mut self) {        
-        let instruction = self.memory[self.program_counter as usize];
+    // Runs whole instructions until at least the requested number of cycles have passed. 
+    //  Returns how many cycles were actually run, since the last instruction can overshoot. 
+    pub fn run_for_cycles(&mut self, cycles: u64) -> u64 {
+        let start = self.cycles;
+        while self.cycles - start < cycles && !self.halted {
+            self.run_next_instruction();
+        }
+
+        self.cycles - start
+    }
+
+    // Returns the number of cycles the instruction took, including any time the CPU 
+    //  spent stalled by DMA because of it
+    pub fn run_next_instruction(&mut self) -> u16 {
+        if self.halted {
+            return 0;
+        }
+
+        // Servicing an interrupt takes the place of the next instruction
+        if self.poll_interrupts() {
+            self.add_cycles(INTERRUPT_CYCLES);
+            return INTERRUPT_CYCLES;
+        }
+
+        // If the log can't be written to, stop tracing instead of stopping the CPU
+        if let Some(mut trace) = self.trace.take() {
+            match trace.log(self) {
+                Ok(()) => self.trace = Some(trace),
+                Err(err) => println!("Could not write trace log: {}", err)
+            }
+        }
+
+        let instruction = self.load(self.program_counter);
         let mode: AddressingMode = map_instruction_to_addressing_mode(instruction);
+
+        self.page_crossed = false;
+        self.extra_cycles = 0;
+
+        if is_unofficial_instruction(instruction) {
+            match self.illegal_opcode_mode {
+                IllegalOpcodeMode::Execute => {},
+                IllegalOpcodeMode::Log => println!("Executing unofficial instruction {} (0x{:02x}) at 0x{:04x}", 
+                    map_instruction_to_name(instruction), instruction, self.program_counter),
+                IllegalOpcodeMode::Trap => {
+                    println!("Trapped on unofficial instruction {} (0x{:02x}) at 0x{:04x}", 
+                        map_instruction_to_name(instruction), instruction, self.program_counter);
+                    self.halted = true;
+                    return 0;
+                }
+            }
+        }
+
         match instruction {
             /* ----- ADC ----- */
             0x69 | 0x65 | 0x75 | 0x6D | 0x7D | 0x79 | 0x61 | 0x71 => self.adc(mode),
             /* ----- AND ----- */
             0x29 | 0x25 | 0x35 | 0x2D | 0x3D | 0x39 | 0x21 | 0x31 => self.and(mode),
             /* ----- ASL ----- */
-            0x0A | 0x06 | 0x16 | 0x0E | 0x1E => self.asl(mode),
+            0x0A | 0x06 | 0x16 | 0x0E | 0x1E => { self.asl(mode); },
             /* ----- BCC ----- */
             0x90 => self.branch(CARRY, false),
             /* ----- BCS ----- */
@@ -188,7 +316,7 @@ impl CPU
             /* ----- BPL ----- */
             0x10 => self.branch(NEGATIVE, false),
             /* ----- BRK ----- */
-            0x00 => println!("BRK is not implemented."),
+            0x00 => self.brk(),
             /* ----- BVC ----- */
             0x50 => self.branch(OVERFLOW, false),
             /* ----- BVS ----- */
@@ -208,15 +336,15 @@ impl CPU
             /* ----- CPY ----- */
             0xC0 | 0xC4 | 0xCC => self.cpy(mode),
             /* ----- DEC ----- */
-            0xC6 | 0xD6 | 0xCE | 0xDE => self.dec(mode),
+            0xC6 | 0xD6 | 0xCE | 0xDE => { self.dec(mode); },
             /* ----- DEX ----- */
             0xCA => self.dex(),
             /* ----- DEY ----- */
             0x88 => self.dey(),
             /* ----- EOR ----- */
-            0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => println!("EOR is not implemented."),
+            0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => self.eor(mode),
             /* ----- INC ----- */
-            0xE6 | 0xF6 | 0xEE | 0xFE => self.inc(mode),
+            0xE6 | 0xF6 | 0xEE | 0xFE => { self.inc(mode); },
             /* ----- INX ----- */
             0xE8 => self.inx(),
             /* ----- INY ----- */
@@ -232,11 +360,11 @@ impl CPU
             /* ----- LDY ----- */
             0xA0 | 0xB4 | 0xA4 | 0xAC | 0xBC => self.ldy(mode),
             /* ----- LSR ----- */
-            0x4A | 0x46 | 0x56 | 0x4E | 0x5E => self.lsr(mode),
+            0x4A | 0x46 | 0x56 | 0x4E | 0x5E => { self.lsr(mode); },
             /* ----- NOP ----- */
             0xEA => {},
             /* ----- ORA ----- */
-            0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => println!("ORA is not implemented. "),
+            0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => self.ora(mode),
             /* ----- PHA ----- */
             0x48 => self.pha(),
             /* ----- PHP ----- */
@@ -246,15 +374,15 @@ impl CPU
             /* ----- PLP ----- */
             0x28 => self.plp(),
             /* ----- ROL ----- */
-            0x2A | 0x26 | 0x36 | 0x2E | 0x3E => self.rol(mode),
+            0x2A | 0x26 | 0x36 | 0x2E | 0x3E => { self.rol(mode); },
             /* ----- ROR ----- */
-            0x6A | 0x66 | 0x76 | 0x6E | 0x7E=> self.ror(mode),
+            0x6A | 0x66 | 0x76 | 0x6E | 0x7E => { self.ror(mode); },
             /* ----- RTI ----- */
-            0x40 => println!("RTI is not implemented."),
+            0x40 => self.rti(),
             /* ----- RTS ----- */
             0x60 => self.rts(),
             /* ----- SBC ----- */
-            0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1=> println!("SBC is not implemented."),
+            0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1 => self.sbc(mode),
             /* ----- SEC ----- */
             0x38 => self.set_carry_bit(),
             /* ----- SED ----- */
@@ -279,13 +407,93 @@ impl CPU
             0x9A => self.txs(),
             /* ----- TYA ----- */
             0x98 => self.tya(),
-            /* ----- Unsupported ----- */
-            _ => println!("Got unrecognized instruction, skipping. {:?}", instruction)
+            /* ----- Unofficial NOPs ----- */
+            0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA |
+            0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 |
+            0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 => {},
+            // The absolute versions still do the read, which is where the page cross penalty comes from
+            0x0C | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => { get_operator_from_addressing_mode(self, mode); },
+            /* ----- LAX ----- */
+            0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => self.lax(mode),
+            /* ----- SAX ----- */
+            0x87 | 0x97 | 0x8F | 0x83 => self.sax(mode),
+            /* ----- SBC (unofficial) ----- */
+            0xEB => self.sbc(mode),
+            /* ----- DCP ----- */
+            0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xC3 | 0xD3 => self.dcp(mode),
+            /* ----- ISB ----- */
+            0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => self.isb(mode),
+            /* ----- SLO ----- */
+            0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => self.slo(mode),
+            /* ----- RLA ----- */
+            0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x23 | 0x33 => self.rla(mode),
+            /* ----- SRE ----- */
+            0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => self.sre(mode),
+            /* ----- RRA ----- */
+            0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => self.rra(mode),
+            /* ----- ANC ----- */
+            0x0B | 0x2B => self.anc(mode),
+            /* ----- ALR ----- */
+            0x4B => self.alr(mode),
+            /* ----- ARR ----- */
+            0x6B => self.arr(mode),
+            /* ----- AXS ----- */
+            0xCB => self.axs(mode),
+            /* ----- LAS ----- */
+            0xBB => self.las(mode),
+            /* ----- KIL ----- */
+            // Log and trap mode already reported it above, whoever is running the CPU
+            //  reports the halt itself.
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                self.halted = true;
+                return 0;
+            },
+            /* ----- XAA ----- */
+            0x8B => self.xaa(mode),
+            /* ----- LXA ----- */
+            0xAB => self.lxa(mode),
+            /* ----- AHX ----- */
+            0x93 | 0x9F => self.store_and_high_byte(mode, self.registers.a & self.registers.x),
+            /* ----- TAS ----- */
+            0x9B => self.tas(mode),
+            /* ----- SHY ----- */
+            0x9C => self.store_and_high_byte(mode, self.registers.y),
+            /* ----- SHX ----- */
+            0x9E => self.store_and_high_byte(mode, self.registers.x),
         }
         
         // Dont advance the program counter if it was a jmp instruction - Austin Haskell 8/21/2021
-        if instruction != 0x4C && instruction != 0x6C && instruction != 0x20 {
-            self.program_counter += addressing_mode_to_program_counter_advancement_amount(mode);
+        match instruction {
+            // JMP, JSR, RTS, BRK and RTI all set the program counter themselves
+            0x4C | 0x6C | 0x20 | 0x60 | 0x00 | 0x40 => {},
+            _ => self.program_counter = self.program_counter.wrapping_add(addressing_mode_to_program_counter_advancement_amount(mode))
+        }
+
+        let mut instruction_cycles = map_instruction_to_cycle_count(instruction) + self.extra_cycles;
+        if self.page_crossed && instruction_has_page_cross_penalty(instruction) {
+            instruction_cycles += 1;
+        }
+
+        self.add_cycles(instruction_cycles as u16);
+
+        // A write to $4014 (or anything else that does DMA) holds the CPU up after the
+        //  instruction finishes. How long can depend on whether this is an odd cycle.
+        let stall_cycles = self.bus.take_stall_cycles(self.cycles % 2 == 1);
+        if stall_cycles != 0 {
+            self.add_cycles(stall_cycles);
+        }
+
+        instruction_cycles as u16 + stall_cycles
+    }
+
+    // Keeps the rest of the bus in step with the CPU and picks up any NMI it raised
+    //  along the way. It gets serviced before the next instruction. 
+    fn add_cycles(&mut self, cycles: u16) {
+        self.cycles += cycles as u64;
+        self.bus.tick(cycles);
+
+        if self.bus.poll_nmi() {
+            self.trigger_nmi();
         }
     }
 
@@ -333,6 +541,10 @@ impl CPU
         self.status = self.status & !OVERFLOW;
     }
 
+    fn set_overflow_bit(&mut self) {
+        self.status = self.status | OVERFLOW;
+    }
+
     fn set_status_bit_if_bit_set(&mut self, bit_to_check: u8, bit_to_set: u8, val: u8) {
         if val & bit_to_check != 0 {
             self.status = self.status | bit_to_set;
@@ -341,12 +553,6 @@ impl CPU
         }
     }
 
-    fn grab_next_byte_and_advance_counter(&mut self) -> u8 {
-        let val = self.memory[self.program_counter as usize];
-        self.program_counter += 1;
-        val
-    }
-
     fn update_negative_and_zero(&mut self, byte: u8) {
         if byte == 0 {
             self.set_zero_bit();
@@ -362,23 +568,35 @@ impl CPU
     }
 
     pub fn load(&mut self, addr: u16) -> u8 {
+        let val = self.bus.read(addr);
         #[cfg(test)]
-        println!("Reading memory address at 0x{:04x} and got value 0x{:02x}", addr, self.memory[addr as usize]);
-        self.memory[addr as usize]
+        println!("Reading memory address at 0x{:04x} and got value 0x{:02x}", addr, val);
+        val
     }
 
     pub fn write(&mut self, addr: u16, data: u8) {
         #[cfg(test)]
         println!("Writing the value 0x{:02x} to address 0x{:04x}", data, addr);
-        self.memory[addr as usize] = data;
+        self.bus.write(addr, data);
     }
 
+    // Low byte first, the same order the real CPU reads them in
     pub fn load16(&mut self, addr: u16) -> u16 {
-        (self.load(addr+1) as u16) << 8 | self.load(addr) as u16
+        let low = self.load(addr) as u16;
+        let high = self.load(addr.wrapping_add(1)) as u16;
+        high << 8 | low
+    }
+
+    // Read-modify-write instructions put the unmodified value back the cycle before they 
+    //  write the result, and anything watching writes (MMC1, $2007) sees both of them
+    fn load_for_modify(&mut self, addr: u16) -> u8 {
+        let val = self.load(addr);
+        self.write(addr, val);
+        val
     }
 
     pub fn write16(&mut self, addr: u16, data: u16) {
-        self.write(addr+1, (data >> 8) as u8);
+        self.write(addr.wrapping_add(1), (data >> 8) as u8);
         self.write(addr,    data       as u8);
     }
 
@@ -386,9 +604,11 @@ impl CPU
         STACK_START_ADDR | self.stack_pointer as u16
     }
 
+    // The stack pointer points at the next free slot, so a push writes then moves down 
+    //  and a pop moves up then reads. 
     fn push_to_stack(&mut self, data: u8) {
-        self.stack_pointer -= 1;
         self.write(self.get_stack_memory_addr(), data);
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
     }
 
     fn push_to_stack_16(&mut self, data: u16) {
@@ -400,10 +620,8 @@ impl CPU
     }
 
     fn pop_from_stack(&mut self) -> u8 {
-        let pop_val = self.load(self.get_stack_memory_addr());
-        self.stack_pointer += 1;
-
-        pop_val
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        self.load(self.get_stack_memory_addr())
     }
 
     fn pop_from_stack_16(&mut self) -> u16 {
@@ -416,22 +634,94 @@ impl CPU
     // Used for debugging - Austin Haskell 8/17/2021
     #[allow(dead_code)]
     pub fn peep_stack(&mut self) -> u8 {
-        self.load(self.get_stack_memory_addr())
+        self.load(STACK_START_ADDR | self.stack_pointer.wrapping_add(1) as u16)
+    }
+
+    // Latches an NMI, it will be serviced before the next instruction regardless of 
+    //  the interrupt disable flag. 
+    pub fn trigger_nmi(&mut self) {
+        self.nmi_pending = true;
+    }
+
+    // IRQ is level triggered, whoever is driving the line needs to release it 
+    //  once the interrupt has been acknowledged. Hardware drives it through Bus::irq, 
+    //  this is for poking the line directly in tests. 
+    #[cfg(test)]
+    pub fn set_irq_line(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
+
+    // NMI always wins over IRQ, IRQ is only taken when interrupts are enabled. 
+    //  Returns true if an interrupt was serviced. 
+    fn poll_interrupts(&mut self) -> bool {
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            self.interrupt(NMI_VECTOR, false);
+            return true;
+        }
+
+        if (self.irq_line || self.bus.irq()) && self.status & INTERRUPT_DISABLE == 0 {
+            self.interrupt(IRQ_VECTOR, false);
+            return true;
+        }
+
+        false
+    }
+
+    // Pushes the return address and status then jumps through the vector. The 
+    //  break flag only exists on the copy of the status that gets pushed, it is how 
+    //  the handler tells a BRK apart from a hardware IRQ. 
+    fn interrupt(&mut self, vector: u16, from_brk: bool) {
+        self.push_to_stack_16(self.program_counter);
+
+        let mut pushed_status = self.status | UNUSED;
+        if from_brk {
+            pushed_status = pushed_status | BREAK;
+        }
+        self.push_to_stack(pushed_status);
+
+        self.set_interrupt_disable_bit();
+        self.program_counter = self.load16(vector);
+    }
+
+    fn brk(&mut self) {
+        // BRK skips over its padding byte, so the return address is PC + 2
+        self.program_counter = self.program_counter.wrapping_add(2);
+
+        // An NMI that shows up during a BRK hijacks it, the B flag still gets pushed
+        //  but the CPU goes through the NMI vector instead. 
+        let vector = if self.nmi_pending { 
+            self.nmi_pending = false;
+            NMI_VECTOR 
+        } else { 
+            IRQ_VECTOR 
+        };
+
+        self.interrupt(vector, true);
+    }
+
+    fn rti(&mut self) {
+        self.status = self.pop_from_stack() & !(BREAK | UNUSED);
+        self.program_counter = self.pop_from_stack_16();
     }
 
     fn branch(&mut self, flag: u8, checking_if_set: bool) {
-        let displacement = self.load(self.program_counter + 1) as i8;
+        let displacement = self.load(self.program_counter.wrapping_add(1)) as i8;
+
+        // The displacement is relative to the instruction after the branch
+        self.program_counter = self.program_counter.wrapping_add(2);
 
         if checking_if_set && self.status & flag != 0 ||
           !checking_if_set && self.status & flag == 0  {
+            let branch_addr = self.program_counter.wrapping_add(displacement as u16);
 
-            println!("Displacement {:?}", displacement);
-            let branch_addr = self.program_counter as i16 + displacement as i16;
-            println!("branching {:?} -> {:?} (displacement of {:?})", self.program_counter , branch_addr as u16, displacement as i8);
-            self.program_counter = branch_addr as u16;
+            // A taken branch costs one more cycle, and another if it lands on a new page
+            self.extra_cycles += 1;
+            if branch_addr & 0xFF00 != self.program_counter & 0xFF00 {
+                self.extra_cycles += 1;
+            }
 
-        } else {
-            self.program_counter += 2;
+            self.program_counter = branch_addr;
         }
     }
 
@@ -440,15 +730,16 @@ impl CPU
     }
 
     fn php(&mut self) {
-        self.push_to_stack(self.status);
+        self.push_to_stack(self.status | BREAK | UNUSED);
     }
 
     fn pla(&mut self) {
         self.registers.a = self.pop_from_stack();
+        self.update_negative_and_zero(self.registers.a);
     }
 
     fn plp(&mut self) {
-        self.status = self.pop_from_stack();
+        self.status = self.pop_from_stack() & !(BREAK | UNUSED);
     }
 
     fn txa(&mut self) {
@@ -499,61 +790,62 @@ impl CPU
         self.registers.y = val;
     }
 
-    fn lsr(&mut self, mode: AddressingMode) {
+    fn lsr(&mut self, mode: AddressingMode) -> u8 {
         if mode != AddressingMode::Accumulator {
             let addr = get_operator_from_addressing_mode(self, mode);
-            let mut val = self.load(addr);
+            let mut val = self.load_for_modify(addr);
 
             self.set_status_bit_if_bit_set(0b0000_0001, CARRY, val);
 
             val = val >> 1;
             self.write(addr, val);
             self.update_negative_and_zero(val);
+            val
         } else {
             self.set_status_bit_if_bit_set(0b0000_0001, CARRY, self.registers.a);
 
             self.registers.a = self.registers.a >> 1;
             self.update_negative_and_zero(self.registers.a);
+            self.registers.a
         }
-
     }
 
-    fn inc(&mut self, mode: AddressingMode) {
+    fn inc(&mut self, mode: AddressingMode) -> u8 {
         let addr = get_operator_from_addressing_mode(self, mode);
-        let mut val = self.load(addr);
+        let mut val = self.load_for_modify(addr);
 
         val = val.wrapping_add(1);
 
         self.update_negative_and_zero(val);
 
         self.write(addr, val);
+        val
     }
 
     fn inx(&mut self) {
-        self.registers.x += 1;
+        self.registers.x = self.registers.x.wrapping_add(1);
         self.update_negative_and_zero(self.registers.x);
     }
 
     fn iny(&mut self) {
-        self.registers.y += 1;
+        self.registers.y = self.registers.y.wrapping_add(1);
         self.update_negative_and_zero(self.registers.y);
     }
 
     fn dex(&mut self) {
-        self.registers.x -= 1;
+        self.registers.x = self.registers.x.wrapping_sub(1);
         self.update_negative_and_zero(self.registers.x);
     }
 
     fn dey(&mut self) {
-        self.registers.y -= 1;
+        self.registers.y = self.registers.y.wrapping_sub(1);
         self.update_negative_and_zero(self.registers.y);
     }
 
     fn and(&mut self, mode: AddressingMode) {
-        let param = self.grab_next_byte_and_advance_counter();
-        let mem_at_location = self.load(param as u16);
+        let addr = get_operator_from_addressing_mode(self, mode);
 
-        self.registers.a = self.registers.a & mem_at_location;
+        self.registers.a = self.registers.a & self.load(addr);
 
         self.update_negative_and_zero(self.registers.a);
     }
@@ -583,7 +875,7 @@ impl CPU
         }
     }
 
-    fn asl(&mut self, mode: AddressingMode) {
+    fn asl(&mut self, mode: AddressingMode) -> u8 {
 
         let mut addr: u16 = 0;
         let mut val: u8;
@@ -592,46 +884,33 @@ impl CPU
             val = self.registers.a; 
         } else {
             addr = get_operator_from_addressing_mode(self, mode);
-            val = self.load(addr); 
+            val = self.load_for_modify(addr); 
         }
 
         self.set_status_bit_if_bit_set(SIGN_BIT, CARRY, val);
 
-        if val == 0 {
-            self.set_zero_bit();
-        } else {
-            self.clear_zero_bit();
-        }
-
         val = val << 1;
 
-        self.set_status_bit_if_bit_set(SIGN_BIT, NEGATIVE, val);
+        self.update_negative_and_zero(val);
 
         if mode == AddressingMode::Accumulator {
             self.registers.a = val;
         } else {
             self.write(addr, val);
         }
+
+        val
     }
 
-    fn rol(&mut self, mode: AddressingMode) {
+    fn rol(&mut self, mode: AddressingMode) -> u8 {
         let mut addr: u16 = 0;
         let mut val: u8;
 
         if mode == AddressingMode::Accumulator {
             val = self.registers.a;
-
-            if val == 0 {
-                self.set_zero_bit();
-                return;
-            } else {
-                self.clear_zero_bit();
-            }
-
         } else {
             addr = get_operator_from_addressing_mode(self, mode);
-            println!("Addr from get_operator 0x{:04x}", addr);
-            val = self.load(addr);
+            val = self.load_for_modify(addr);
         }
 
         let old_carry = self.status & CARRY;
@@ -642,33 +921,26 @@ impl CPU
             val = val | 0b0000_0001;
         }
 
-        self.set_status_bit_if_bit_set(SIGN_BIT, NEGATIVE, val);
+        self.update_negative_and_zero(val);
 
         if mode == AddressingMode::Accumulator {
             self.registers.a = val;
         } else {
             self.write(addr, val);
         }
+
+        val
     }
 
-    fn ror(&mut self, mode: AddressingMode) {
+    fn ror(&mut self, mode: AddressingMode) -> u8 {
         let mut addr: u16 = 0;
         let mut val: u8;
 
         if mode == AddressingMode::Accumulator {
             val = self.registers.a;
-
-            if val == 0 {
-                self.set_zero_bit();
-                return;
-            } else {
-                self.clear_zero_bit();
-            }
-
         } else {
             addr = get_operator_from_addressing_mode(self, mode);
-            println!("Addr from get_operator 0x{:04x}", addr);
-            val = self.load(addr);
+            val = self.load_for_modify(addr);
         }
 
         let old_carry = self.status & CARRY;
@@ -679,57 +951,47 @@ impl CPU
             val = val | SIGN_BIT;
         }
 
-        self.set_status_bit_if_bit_set(SIGN_BIT, NEGATIVE, val);
+        self.update_negative_and_zero(val);
 
         if mode == AddressingMode::Accumulator {
             self.registers.a = val;
         } else {
             self.write(addr, val);
         }
+
+        val
     }
 
     fn cpx(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
         let val = self.load(addr);
 
-        if self.registers.x > val {
-            self.set_carry_bit();
-        } else {
-            self.clear_carry_bit();
-        }
-
-        if self.registers.x == val {
-            self.set_zero_bit();
-        } else {
-            self.clear_zero_bit();
-        }
-
-        self.set_status_bit_if_bit_set(SIGN_BIT, NEGATIVE, val);
+        self.compare(self.registers.x, val);
     }
 
     fn cpy(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
         let val = self.load(addr);
 
-        if self.registers.y > val {
-            self.set_carry_bit();
-        } else {
-            self.clear_carry_bit();
-        }
+        self.compare(self.registers.y, val);
+    }
 
-        if self.registers.y == val {
-            self.set_zero_bit();
-        } else {
-            self.clear_zero_bit();
-        }
+    fn adc(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = self.load(addr);
 
-        self.set_status_bit_if_bit_set(SIGN_BIT, NEGATIVE, val);
+        self.add_to_accumulator(val);
     }
 
-    fn adc(&mut self, mode: AddressingMode) {
+    // SBC is ADC with the operand inverted, the carry flag acts as an inverted borrow
+    fn sbc(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
         let val = self.load(addr);
 
+        self.add_to_accumulator(!val);
+    }
+
+    fn add_to_accumulator(&mut self, val: u8) {
         let mut scratch_value: u16 = val as u16 + self.registers.a as u16;
         if self.status & CARRY != 0 {
             scratch_value += 1;
@@ -748,7 +1010,7 @@ impl CPU
 
         if sign_of_sum != register_a_sign && 
            sign_of_sum != operand_a_sign {
-            self.status = self.status | OVERFLOW;
+            self.set_overflow_bit();
         } else {
             self.clear_overflow_bit();
         }
@@ -758,15 +1020,16 @@ impl CPU
         self.update_negative_and_zero(self.registers.a);
     }
 
-    fn dec(&mut self, mode: AddressingMode) {
+    fn dec(&mut self, mode: AddressingMode) -> u8 {
         let addr = get_operator_from_addressing_mode(self, mode);
-        let mut val = self.load(addr);
+        let mut val = self.load_for_modify(addr);
 
         val = val.wrapping_sub(1);
 
         self.update_negative_and_zero(val);
 
         self.write(addr, val);
+        val
     }
 
     fn sta(&mut self, mode: AddressingMode) {
@@ -790,32 +1053,196 @@ impl CPU
     fn jsr(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
 
-        self.push_to_stack_16(self.program_counter - 1);
+        // The return address pushed is the last byte of the JSR, RTS makes up the difference
+        self.push_to_stack_16(self.program_counter.wrapping_add(2));
         self.program_counter = addr;
     }
 
     fn rts(&mut self) {
-        self.program_counter = self.pop_from_stack_16();
-    } 
+        self.program_counter = self.pop_from_stack_16().wrapping_add(1);
+    }
 
     fn cmp(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
         let val = self.load(addr);
 
-        let answer: i8 = (self.registers.a as i8).wrapping_sub(val as i8);
-
-        self.update_negative_and_zero(answer as u8);
+        self.compare(self.registers.a, val);
+    }
 
-        if self.registers.a >= val {
+    // CMP, CPX and CPY all behave like a subtraction that throws away the result
+    fn compare(&mut self, register: u8, val: u8) {
+        if register >= val {
             self.set_carry_bit();
         } else {
             self.clear_carry_bit();
         }
+
+        self.update_negative_and_zero(register.wrapping_sub(val));
     }
 
     fn eor(&mut self, mode: AddressingMode) {
         let addr = get_operator_from_addressing_mode(self, mode);
 
         self.registers.a = self.registers.a ^ self.load(addr);
+
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    fn ora(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+
+        self.registers.a = self.registers.a | self.load(addr);
+
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    /* ----- Unofficial instructions ----- */
+
+    fn lax(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = self.load(addr);
+
+        self.registers.a = val;
+        self.registers.x = val;
+        self.update_negative_and_zero(val);
+    }
+
+    fn sax(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+
+        self.write(addr, self.registers.a & self.registers.x);
+    }
+
+    fn dcp(&mut self, mode: AddressingMode) {
+        let val = self.dec(mode);
+
+        self.compare(self.registers.a, val);
+    }
+
+    fn isb(&mut self, mode: AddressingMode) {
+        let val = self.inc(mode);
+
+        self.add_to_accumulator(!val);
+    }
+
+    fn slo(&mut self, mode: AddressingMode) {
+        let val = self.asl(mode);
+
+        self.registers.a = self.registers.a | val;
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    fn rla(&mut self, mode: AddressingMode) {
+        let val = self.rol(mode);
+
+        self.registers.a = self.registers.a & val;
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    fn sre(&mut self, mode: AddressingMode) {
+        let val = self.lsr(mode);
+
+        self.registers.a = self.registers.a ^ val;
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    fn rra(&mut self, mode: AddressingMode) {
+        let val = self.ror(mode);
+
+        self.add_to_accumulator(val);
+    }
+
+    // AND, then the carry gets a copy of the negative flag
+    fn anc(&mut self, mode: AddressingMode) {
+        self.and(mode);
+
+        self.set_status_bit_if_bit_set(SIGN_BIT, CARRY, self.registers.a);
+    }
+
+    fn alr(&mut self, mode: AddressingMode) {
+        self.and(mode);
+
+        self.lsr(AddressingMode::Accumulator);
+    }
+
+    // AND then ROR, except the carry and overflow come from bits 6 and 5 of the result
+    fn arr(&mut self, mode: AddressingMode) {
+        self.and(mode);
+        let val = self.ror(AddressingMode::Accumulator);
+
+        self.set_status_bit_if_bit_set(0b0100_0000, CARRY, val);
+
+        if ((val >> 6) ^ (val >> 5)) & 0x01 != 0 {
+            self.set_overflow_bit();
+        } else {
+            self.clear_overflow_bit();
+        }
+    }
+
+    // X = (A & X) - M, without borrow. Sets the flags the same way CMP does
+    fn axs(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = self.load(addr);
+
+        let a_and_x = self.registers.a & self.registers.x;
+        self.compare(a_and_x, val);
+        self.registers.x = a_and_x.wrapping_sub(val);
+    }
+
+    fn las(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = self.load(addr) & self.stack_pointer;
+
+        self.registers.a = val;
+        self.registers.x = val;
+        self.stack_pointer = val;
+        self.update_negative_and_zero(val);
+    }
+
+    /* ----- Unstable instructions ----- */
+    // These depend on analog effects that vary from chip to chip. The magic constant 
+    //  and the high byte AND are what most chips do, and what the test roms expect. 
+
+    // A = (A | magic) & X & M
+    fn xaa(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = self.load(addr);
+
+        self.registers.a = (self.registers.a | UNSTABLE_MAGIC) & self.registers.x & val;
+        self.update_negative_and_zero(self.registers.a);
+    }
+
+    // A = X = (A | magic) & M
+    fn lxa(&mut self, mode: AddressingMode) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let val = (self.registers.a | UNSTABLE_MAGIC) & self.load(addr);
+
+        self.registers.a = val;
+        self.registers.x = val;
+        self.update_negative_and_zero(val);
+    }
+
+    // SP = A & X, then stored the same way as AHX
+    fn tas(&mut self, mode: AddressingMode) {
+        self.stack_pointer = self.registers.a & self.registers.x;
+
+        self.store_and_high_byte(mode, self.stack_pointer);
+    }
+
+    // AHX, SHX, SHY and TAS store the value ANDed with the high byte of the base address
+    //  plus one. When the index crosses a page the high byte of the address gets 
+    //  replaced by the value too. 
+    fn store_and_high_byte(&mut self, mode: AddressingMode, val: u8) {
+        let addr = get_operator_from_addressing_mode(self, mode);
+        let index = match mode {
+            AddressingMode::Absolute_X => self.registers.x,
+            _ => self.registers.y
+        };
+        let base = addr.wrapping_sub(index as u16);
+
+        let val = val & ((base >> 8) as u8).wrapping_add(1);
+        let addr = if crosses_page(base, addr) { (val as u16) << 8 | (addr & 0x00FF) } else { addr };
+
+        self.write(addr, val);
     }
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::addressing_modes::AddressingMode;
+use crate::bus::Bus;
+use crate::util::*;
+
+pub struct DisassembledInstruction {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub text: String
+}
+
+// Prints as "8000  A9 01     LDA #$01"
+impl fmt::Display for DisassembledInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let raw_bytes: Vec<String> = self.bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+        write!(f, "{:04X}  {:<8}  {}", self.address, raw_bytes.join(" "), self.text)
+    }
+}
+
+// How many bytes an instruction takes up, including the opcode
+pub fn instruction_length(mode: AddressingMode) -> u16 {
+    match mode {
+        // Branches move the program counter themselves, but they are still two bytes
+        AddressingMode::Relative => 2,
+        _ => addressing_mode_to_program_counter_advancement_amount(mode)
+    }
+}
+
+// Disassembles a single instruction at address. read is only ever given addresses
+//  from address up to the end of the instruction.
+pub fn disassemble_instruction<F: Fn(u16) -> u8>(read: F, address: u16) -> DisassembledInstruction {
+    let instruction = read(address);
+    let mode = map_instruction_to_addressing_mode(instruction);
+    let length = instruction_length(mode);
+
+    let bytes: Vec<u8> = (0..length).map(|offset| read(address.wrapping_add(offset))).collect();
+
+    DisassembledInstruction {
+        address,
+        text: format_instruction(address, &bytes, mode),
+        bytes
+    }
+}
+
+// Disassembles a block of bytes as if it was loaded at start_address. If the last
+//  instruction is cut off the leftover bytes come out as .db
+pub fn disassemble(bytes: &[u8], start_address: u16) -> Vec<DisassembledInstruction> {
+    let mut instructions = Vec::new();
+    let mut offset: usize = 0;
+
+    while offset < bytes.len() {
+        let address = start_address.wrapping_add(offset as u16);
+        let mode = map_instruction_to_addressing_mode(bytes[offset]);
+        let length = instruction_length(mode) as usize;
+
+        if offset + length > bytes.len() {
+            for (index, byte) in bytes[offset..].iter().enumerate() {
+                instructions.push(DisassembledInstruction {
+                    address: address.wrapping_add(index as u16),
+                    bytes: vec![*byte],
+                    text: format!(".db ${:02X}", byte)
+                });
+            }
+            break;
+        }
+
+        let base = offset;
+        instructions.push(disassemble_instruction(|addr| bytes[base + addr.wrapping_sub(address) as usize], address));
+        offset += length;
+    }
+
+    instructions
+}
+
+// Where a 16KB PRG bank usually shows up. NROM only has one or two, which go at $C000 or
+//  $8000 and $C000, and bigger boards mostly keep their last bank fixed at $C000 and 
+//  switch the rest in at $8000.
+pub fn program_bank_address(bank: usize, bank_count: usize) -> u16 {
+    if bank + 1 == bank_count { 0xC000 } else { 0x8000 }
+}
+
+// Disassembles count instructions straight out of whatever is on the bus. Uses peek so
+//  looking at memory mapped registers doesn't change anything.
+#[allow(dead_code)]
+pub fn disassemble_from_bus<B: Bus>(bus: &B, start_address: u16, count: usize) -> Vec<DisassembledInstruction> {
+    let mut instructions = Vec::new();
+    let mut address = start_address;
+
+    for _ in 0..count {
+        let instruction = disassemble_instruction(|addr| bus.peek(addr), address);
+        address = address.wrapping_add(instruction.bytes.len() as u16);
+        instructions.push(instruction);
+    }
+
+    instructions
+}
+
+fn format_instruction(address: u16, bytes: &[u8], mode: AddressingMode) -> String {
+    let name = map_instruction_to_name(bytes[0]);
+
+    let byte = || bytes[1];
+    let word = || (bytes[2] as u16) << 8 | bytes[1] as u16;
+
+    match mode {
+        AddressingMode::Implied => name.to_string(),
+        AddressingMode::Accumulator => format!("{} A", name),
+        AddressingMode::Immediate => format!("{} #${:02X}", name, byte()),
+        AddressingMode::ZeroPage => format!("{} ${:02X}", name, byte()),
+        AddressingMode::ZeroPage_X => format!("{} ${:02X},X", name, byte()),
+        AddressingMode::ZeroPage_Y => format!("{} ${:02X},Y", name, byte()),
+        AddressingMode::Absolute => format!("{} ${:04X}", name, word()),
+        AddressingMode::Absolute_X => format!("{} ${:04X},X", name, word()),
+        AddressingMode::Absolute_Y => format!("{} ${:04X},Y", name, word()),
+        AddressingMode::Indirect_X => format!("{} (${:02X},X)", name, byte()),
+        AddressingMode::Indirect_Y => format!("{} (${:02X}),Y", name, byte()),
+        AddressingMode::Indirect => format!("{} (${:04X})", name, word()),
+        AddressingMode::Relative => {
+            let target = address.wrapping_add(2).wrapping_add(byte() as i8 as u16);
+            format!("{} ${:04X}", name, target)
+        }
+    }
+}
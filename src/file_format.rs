@@ -1,82 +1,356 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use zip::ZipArchive;
+
+use crate::ppu::Mirroring;
+
+pub const HEADER_SIZE: usize = 16;
+pub const TRAINER_SIZE: usize = 512;
+pub const TRAINER_ADDRESS: u16 = 0x7000;
+pub const PROGRAM_ROM_BANK_SIZE: usize = 16384;
+pub const CHAR_ROM_BANK_SIZE: usize = 8192;
+pub const PLAY_CHOICE_INST_ROM_SIZE: usize = 8192;
+pub const PLAY_CHOICE_PROM_SIZE: usize = 32;
+pub const TITLE_SIZE: usize = 128;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HeaderFormat {
+	INES,
+	NES2
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TvSystem {
+	NTSC,
+	PAL,
+	MultipleRegion,
+	Dendy
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ConsoleType {
+	NES,
+	VsSystem,
+	PlayChoice10,
+	Extended
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RomError {
+	// Shorter than the 16 byte header
+	TruncatedHeader,
+	// Doesn't start with "NES" and $1A
+	BadMagic,
+	TruncatedTrainer,
+	TruncatedProgramRom { expected: usize, found: usize },
+	TruncatedCharRom { expected: usize, found: usize },
+	UnsupportedMapper(u16),
+	// Couldn't read the file, or the archive it was in
+	Io(String),
+	// A .zip without a .nes file in it
+	NoRomInArchive
+}
+
+impl fmt::Display for RomError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RomError::TruncatedHeader => write!(f, "File is too short to have an iNES header"),
+			RomError::BadMagic => write!(f, "File is not an iNES ROM, the header doesn't start with NES<EOF>"),
+			RomError::TruncatedTrainer => write!(f, "Header says there is a trainer but the file ends before it does"),
+			RomError::TruncatedProgramRom { expected, found } => write!(f, "Expected {} bytes of PRG ROM but only found {}", expected, found),
+			RomError::TruncatedCharRom { expected, found } => write!(f, "Expected {} bytes of CHR ROM but only found {}", expected, found),
+			RomError::UnsupportedMapper(mapper) => write!(f, "Mapper {} is not supported", mapper),
+			RomError::Io(err) => write!(f, "Could not read ROM: {}", err),
+			RomError::NoRomInArchive => write!(f, "Archive does not contain a .nes file")
+		}
+	}
+}
+
+impl Error for RomError { }
+
 pub struct INES {
 	pub header: INESHeader,
-	pub trainer: Option<[u8; 512]>, 
+	pub trainer: Option<[u8; TRAINER_SIZE]>,
 	pub program_rom: Vec<u8>,
-	pub char_rom: Vec<u8>, 
-	pub play_choice_inst_rom: Vec<u8>,
-	pub play_choice_prom: Vec<u8>,
+	pub char_rom: Vec<u8>,
+	// Only PlayChoice-10 carts have these, we keep them but nothing runs them
+	pub play_choice_inst_rom: Option<Vec<u8>>,
+	pub play_choice_prom: Option<Vec<u8>>,
 	pub title: Option<Vec<u8>>
 }
 
+// See https://www.nesdev.org/wiki/INES and https://www.nesdev.org/wiki/NES_2.0
+//  Sizes are all in bytes, whichever way the header stores them.
 pub struct INESHeader {
-	pub nes_magic_present: bool,
-	pub program_rom_size: u8, 
-	pub char_rom_size: u8,
-	pub flag_group_6: u8,
-	pub flag_group_7: u8, 
-	pub flag_group_8: u8,
-	pub flag_group_9: u8,
-	pub flag_group_10: u8,
-	pub extra_padding: [u8; 5]
+	pub format: HeaderFormat,
+	pub program_rom_size: usize,
+	pub char_rom_size: usize,
+	pub mapper: u16,
+	pub submapper: u8,
+	pub mirroring: Mirroring,
+	pub four_screen: bool,
+	pub battery: bool,
+	pub trainer_present: bool,
+	pub program_ram_size: usize,
+	pub program_nvram_size: usize,
+	pub char_ram_size: usize,
+	pub char_nvram_size: usize,
+	pub tv_system: TvSystem,
+	pub console_type: ConsoleType
 }
 
 impl INESHeader {
-	pub fn from_bytes(bytes: &[u8]) -> Self {
-
-		println!("Got header of [{:?}]", bytes);
-
-		let nes_magic_present = 
-			bytes[0] == 'N' as u8 && 
-			bytes[1] == 'E' as u8 && 
-			bytes[2] == 'S' as u8 &&
-			bytes[3] == 26;
-
-		Self {
-			nes_magic_present: nes_magic_present,
-			program_rom_size: bytes[4],
-			char_rom_size: bytes[5],
-			flag_group_6: bytes[6],
-			flag_group_7: bytes[7],
-			flag_group_8: bytes[8],
-			flag_group_9: bytes[9],
-			flag_group_10: bytes[10],
-			extra_padding: [bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]]
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, RomError> {
+		if bytes.len() < HEADER_SIZE {
+			return Err(RomError::TruncatedHeader);
 		}
+
+		if bytes[0..4] != [b'N', b'E', b'S', 0x1A] {
+			return Err(RomError::BadMagic);
+		}
+
+		let flags_6 = bytes[6];
+		let flags_7 = bytes[7];
+
+		// NES 2.0 puts 0b10 in bits 2 and 3 of byte 7
+		let format = if flags_7 & 0x0C == 0x08 { HeaderFormat::NES2 } else { HeaderFormat::INES };
+
+		let four_screen = flags_6 & 0x08 != 0;
+		let mirroring = if four_screen {
+			Mirroring::FourScreen
+		}
+		else if flags_6 & 0x01 != 0 {
+			Mirroring::Vertical
+		}
+		else {
+			Mirroring::Horizontal
+		};
+
+		let console_type = match flags_7 & 0x03 {
+			0 => ConsoleType::NES,
+			1 => ConsoleType::VsSystem,
+			2 => ConsoleType::PlayChoice10,
+			_ => ConsoleType::Extended
+		};
+
+		let mut header = Self {
+			format,
+			program_rom_size: bytes[4] as usize * PROGRAM_ROM_BANK_SIZE,
+			char_rom_size: bytes[5] as usize * CHAR_ROM_BANK_SIZE,
+			mapper: (flags_6 >> 4) as u16 | (flags_7 & 0xF0) as u16,
+			submapper: 0,
+			mirroring,
+			four_screen,
+			battery: flags_6 & 0x02 != 0,
+			trainer_present: flags_6 & 0x04 != 0,
+			program_ram_size: 0,
+			program_nvram_size: 0,
+			char_ram_size: 0,
+			char_nvram_size: 0,
+			tv_system: TvSystem::NTSC,
+			console_type
+		};
+
+		match format {
+			HeaderFormat::NES2 => {
+				header.mapper |= ((bytes[8] & 0x0F) as u16) << 8;
+				header.submapper = bytes[8] >> 4;
+				header.program_rom_size = nes2_rom_size(bytes[4], bytes[9] & 0x0F, PROGRAM_ROM_BANK_SIZE);
+				header.char_rom_size = nes2_rom_size(bytes[5], bytes[9] >> 4, CHAR_ROM_BANK_SIZE);
+				header.program_ram_size = nes2_ram_size(bytes[10] & 0x0F);
+				header.program_nvram_size = nes2_ram_size(bytes[10] >> 4);
+				header.char_ram_size = nes2_ram_size(bytes[11] & 0x0F);
+				header.char_nvram_size = nes2_ram_size(bytes[11] >> 4);
+				header.tv_system = match bytes[12] & 0x03 {
+					0 => TvSystem::NTSC,
+					1 => TvSystem::PAL,
+					2 => TvSystem::MultipleRegion,
+					_ => TvSystem::Dendy
+				};
+			},
+			HeaderFormat::INES => {
+				// Old dumping tools wrote their name ("DiskDude!") over the end of the header,
+				//  if that happened the top half of the mapper number is garbage
+				if bytes[12..16].iter().any(|byte| *byte != 0) {
+					header.mapper &= 0x0F;
+				}
+
+				// 0 here means 8KB for compatibility
+				let program_ram_size = if bytes[8] == 0 { 1 } else { bytes[8] as usize } * 8192;
+				if header.battery {
+					header.program_nvram_size = program_ram_size;
+				}
+				else {
+					header.program_ram_size = program_ram_size;
+				}
+
+				// No CHR ROM means the cartridge has CHR RAM instead
+				if header.char_rom_size == 0 {
+					header.char_ram_size = CHAR_ROM_BANK_SIZE;
+				}
+
+				header.tv_system = if bytes[9] & 0x01 != 0 { TvSystem::PAL } else { TvSystem::NTSC };
+			}
+		}
+
+		Ok(header)
 	}
 
 	pub fn is_trainer_present(self: &Self) -> bool {
-		(self.flag_group_6 & 0x04) != 0
-	} 
+		self.trainer_present
+	}
+}
+
+// One line summary for when a ROM gets loaded, e.g. "NES 2.0, mapper 4.1, 256KB PRG, 128KB CHR, battery"
+impl fmt::Display for INESHeader {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.format {
+			HeaderFormat::INES => write!(f, "iNES, mapper {}", self.mapper)?,
+			HeaderFormat::NES2 => write!(f, "NES 2.0, mapper {}.{}", self.mapper, self.submapper)?
+		}
+
+		write!(f, ", {}KB PRG", self.program_rom_size / 1024)?;
+		if self.char_rom_size > 0 {
+			write!(f, ", {}KB CHR", self.char_rom_size / 1024)?;
+		}
+		else {
+			write!(f, ", CHR RAM")?;
+		}
+
+		if self.four_screen {
+			write!(f, ", four-screen")?;
+		}
+		if self.battery {
+			write!(f, ", battery")?;
+		}
+		Ok(())
+	}
+}
+
+// If the top nibble is $F the size is written as 2^E * (M*2 + 1), with EEEEEEMM in the low byte
+fn nes2_rom_size(lsb: u8, msb: u8, bank_size: usize) -> usize {
+	if msb == 0x0F {
+		let exponent = (lsb >> 2) as u32;
+		let multiplier = (lsb & 0x03) as usize * 2 + 1;
+		2usize.saturating_pow(exponent).saturating_mul(multiplier)
+	}
+	else {
+		(((msb as usize) << 8) | lsb as usize) * bank_size
+	}
+}
+
+// RAM sizes are a shift count, 64 << n, with 0 meaning there is none
+fn nes2_ram_size(shift: u8) -> usize {
+	if shift == 0 { 0 } else { 64 << shift }
 }
 
 impl INES {
-	pub fn from_bytes(bytes: Vec<u8>) -> Self {
+	// Loads a .nes file, or the first .nes file inside a .zip, or a gzipped .nes
+	pub fn from_file(path: &Path) -> Result<Self, RomError> {
+		Self::from_bytes(read_rom_file(path)?)
+	}
+
+	pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, RomError> {
+		let header = INESHeader::from_bytes(&bytes)?;
 
-		println!("Got {:} bytes for ines file. ", bytes.len());
+		let mut offset = HEADER_SIZE;
 
-		let header = INESHeader::from_bytes(&bytes[0..16]);
+		let trainer = if header.is_trainer_present() {
+			if bytes.len() - offset < TRAINER_SIZE {
+				return Err(RomError::TruncatedTrainer);
+			}
 
-		let trainer_offset = if header.is_trainer_present() { 512 } else { 0 };
+			let mut trainer = [0; TRAINER_SIZE];
+			trainer.copy_from_slice(&bytes[offset..offset + TRAINER_SIZE]);
+			offset += TRAINER_SIZE;
+			Some(trainer)
+		}
+		else {
+			None
+		};
 
-		let prog_offsets: (usize, usize) = (trainer_offset + 16, 16384 * header.program_rom_size as usize + trainer_offset);
-		let char_offsets: (usize, usize) = (prog_offsets.1, 8192 * header.char_rom_size as usize + prog_offsets.1);
+		if bytes.len() - offset < header.program_rom_size {
+			return Err(RomError::TruncatedProgramRom { expected: header.program_rom_size, found: bytes.len() - offset });
+		}
+		let program_rom = bytes[offset..offset + header.program_rom_size].to_vec();
+		offset += header.program_rom_size;
 
-		let program_rom = bytes[prog_offsets.0..prog_offsets.1].to_vec(); 
-		let char_rom = if header.char_rom_size != 0 { bytes[char_offsets.0..char_offsets.1].to_vec() } else { Vec::new() }; 
+		if bytes.len() - offset < header.char_rom_size {
+			return Err(RomError::TruncatedCharRom { expected: header.char_rom_size, found: bytes.len() - offset });
+		}
+		let char_rom = bytes[offset..offset + header.char_rom_size].to_vec();
+		offset += header.char_rom_size;
 
-		println!("--------------- PROGRAM ROM ---------------");
-		crate::util::print_memory_block(&program_rom, 16, true, (trainer_offset + 16) as u32, true);
-		println!("------------ END OF PROGRAM ROM ------------");
-			println!("Trainer is {:} present", if header.is_trainer_present() {""} else {"NOT"});
+		let mut play_choice_inst_rom = None;
+		let mut play_choice_prom = None;
+		if header.console_type == ConsoleType::PlayChoice10 && bytes.len() >= offset + PLAY_CHOICE_INST_ROM_SIZE {
+			play_choice_inst_rom = Some(bytes[offset..offset + PLAY_CHOICE_INST_ROM_SIZE].to_vec());
+			offset += PLAY_CHOICE_INST_ROM_SIZE;
+
+			if bytes.len() >= offset + PLAY_CHOICE_PROM_SIZE {
+				play_choice_prom = Some(bytes[offset..offset + PLAY_CHOICE_PROM_SIZE].to_vec());
+				offset += PLAY_CHOICE_PROM_SIZE;
+			}
+		}
 
-		Self {
-			header: header, 
-			trainer: None,
-			program_rom: program_rom,
-			char_rom: char_rom,
-			play_choice_inst_rom: Vec::new(),
-			play_choice_prom: Vec::new(),
-			title: None
+		// Some dumps have a title (127 or 128 bytes) tacked on the end. Anything else 
+		//  left over is just padding. 
+		let title = if bytes.len() - offset == TITLE_SIZE - 1 || bytes.len() - offset == TITLE_SIZE {
+			Some(bytes[offset..].to_vec())
 		}
+		else {
+			None
+		};
+
+		Ok(Self {
+			header,
+			trainer,
+			program_rom,
+			char_rom,
+			play_choice_inst_rom,
+			play_choice_prom,
+			title
+		})
+	}
+
+	// The title with its padding taken off, if the dump has one
+	pub fn title_text(&self) -> Option<String> {
+		let title = self.title.as_ref()?;
+		let text = String::from_utf8_lossy(title).trim_end_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+		if text.is_empty() { None } else { Some(text) }
 	}
-}
\ No newline at end of file
+}
+
+fn read_rom_file(path: &Path) -> Result<Vec<u8>, RomError> {
+	let io_error = |err: &dyn Error| RomError::Io(err.to_string());
+
+	let mut file = File::open(path).map_err(|err| io_error(&err))?;
+	let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_lowercase();
+
+	let mut bytes = Vec::new();
+	match extension.as_str() {
+		"zip" => {
+			let mut archive = ZipArchive::new(file).map_err(|err| io_error(&err))?;
+			let rom_name = archive.file_names()
+				.find(|name| name.to_lowercase().ends_with(".nes"))
+				.map(|name| name.to_string())
+				.ok_or(RomError::NoRomInArchive)?;
+
+			let mut rom = archive.by_name(&rom_name).map_err(|err| io_error(&err))?;
+			rom.read_to_end(&mut bytes).map_err(|err| io_error(&err))?;
+		},
+		"gz" => {
+			GzDecoder::new(file).read_to_end(&mut bytes).map_err(|err| io_error(&err))?;
+		},
+		_ => {
+			file.read_to_end(&mut bytes).map_err(|err| io_error(&err))?;
+		}
+	}
+
+	Ok(bytes)
+}
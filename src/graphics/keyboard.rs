@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use minifb::Key;
+
+use crate::controller::buttons::*;
+
+// Everything that can be named in a key mapping file, by the name minifb gives it
+const MAPPABLE_KEYS: [Key; 62] = [
+	Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+	Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+	Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+	Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4, Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+	Key::Up, Key::Down, Key::Left, Key::Right,
+	Key::Space, Key::Enter, Key::Tab, Key::Backspace,
+	Key::LeftShift, Key::RightShift, Key::LeftCtrl, Key::RightCtrl, Key::LeftAlt, Key::RightAlt,
+	Key::Comma, Key::Period
+];
+
+// Which keys press which buttons on which controller. A mapping file looks like
+//  { "player_1": { "A": "X", "B": "Z", "Start": "Enter", ... }, "player_2": { ... } }
+//  and anything it leaves out keeps the default.
+pub struct KeyMapping {
+	// Key, controller (0 or 1), button
+	bindings: Vec<(Key, usize, u8)>
+}
+
+impl KeyMapping {
+	pub fn new() -> Self {
+		Self {
+			bindings: vec![
+				(Key::X, 0, BUTTON_A),
+				(Key::Z, 0, BUTTON_B),
+				(Key::RightShift, 0, BUTTON_SELECT),
+				(Key::Enter, 0, BUTTON_START),
+				(Key::Up, 0, BUTTON_UP),
+				(Key::Down, 0, BUTTON_DOWN),
+				(Key::Left, 0, BUTTON_LEFT),
+				(Key::Right, 0, BUTTON_RIGHT)
+			]
+		}
+	}
+
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let contents = read_to_string(path).map_err(|err| err.to_string())?;
+		Self::from_json(&contents)
+	}
+
+	pub fn from_json(json: &str) -> Result<Self, String> {
+		let players: HashMap<String, HashMap<String, String>> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+
+		let mut mapping = Self::new();
+		for (player, buttons) in players {
+			let controller = match player.as_str() {
+				"player_1" => 0,
+				"player_2" => 1,
+				_ => return Err(format!("Unknown player [{}], expected player_1 or player_2", player))
+			};
+
+			for (button, key) in buttons {
+				let button = parse_button(&button).ok_or(format!("Unknown button [{}]", button))?;
+				let key = parse_key(&key).ok_or(format!("Unknown key [{}]", key))?;
+				mapping.bind(key, controller, button);
+			}
+		}
+
+		Ok(mapping)
+	}
+
+	// Replaces whatever the key or the button were bound to before
+	pub fn bind(&mut self, key: Key, controller: usize, button: u8) {
+		self.bindings.retain(|(bound_key, bound_controller, bound_button)| {
+			*bound_key != key && !(*bound_controller == controller && *bound_button == button)
+		});
+		self.bindings.push((key, controller, button));
+	}
+
+	// What each controller should have held down given the keys that are
+	pub fn buttons(&self, pressed_keys: &[Key]) -> [u8; 2] {
+		let mut buttons = [0; 2];
+		for (key, controller, button) in self.bindings.iter() {
+			if pressed_keys.contains(key) {
+				buttons[*controller] |= button;
+			}
+		}
+		buttons
+	}
+}
+
+fn parse_button(name: &str) -> Option<u8> {
+	match name.to_lowercase().as_str() {
+		"a" => Some(BUTTON_A),
+		"b" => Some(BUTTON_B),
+		"select" => Some(BUTTON_SELECT),
+		"start" => Some(BUTTON_START),
+		"up" => Some(BUTTON_UP),
+		"down" => Some(BUTTON_DOWN),
+		"left" => Some(BUTTON_LEFT),
+		"right" => Some(BUTTON_RIGHT),
+		_ => None
+	}
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+	MAPPABLE_KEYS.iter().find(|key| format!("{:?}", key).eq_ignore_ascii_case(name)).copied()
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use minifb::*;
+
+use crate::graphics::palette::frame_to_rgb;
+
+pub const WIDTH: usize = 256;
+pub const HEIGHT: usize = 240;
+
+// Roughly 60 frames a second, the same as an NTSC NES
+const FRAME_TIME: Duration = Duration::from_micros(16_639);
+
+pub struct WindowsDisplay {
+	window: Window,
+	buffer: Vec<u32>
+}
+
+impl WindowsDisplay {
+	pub fn new() -> Self {
+		let options = WindowOptions {
+		        scale: Scale::X2,
+		        ..WindowOptions::default()
+		    };
+	    let mut window = Window::new(
+		        "NES Emulator - ESC to exit",
+		        WIDTH,
+		        HEIGHT,
+		        options,
+		    )
+		    .expect("Unable to open window");
+		window.limit_update_rate(Some(FRAME_TIME));
+
+		Self {
+			window,
+			buffer: vec![0; WIDTH * HEIGHT]
+		}
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.window.is_open() && !self.window.is_key_down(Key::Escape)
+	}
+
+	pub fn pressed_keys(&self) -> Vec<Key> {
+		self.window.get_keys()
+	}
+
+	// frame is one NES palette index per pixel, straight from the PPU
+	pub fn update(&mut self, frame: &[u8]) {
+		frame_to_rgb(frame, &mut self.buffer);
+
+		self.window.update_with_buffer(&self.buffer, WIDTH, HEIGHT).unwrap();
+	}
+}
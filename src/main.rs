@@ -1,19 +1,48 @@
-use std::io::Read;
-use std::fs::File;
-
 mod file_format;
 mod cpu;
+mod bus;
+mod trace;
+mod disassembler;
+mod assembler;
 mod addressing_modes;
 mod util;
 mod integration_tests;
 mod graphics {
-    mod windows_display;
+    #[cfg(feature = "window")]
+    pub mod windows_display;
+    #[cfg(feature = "window")]
+    pub mod palette;
+    #[cfg(feature = "window")]
+    pub mod keyboard;
+}
+mod audio {
+    pub mod apu;
+    pub mod pulse;
+    pub mod triangle;
+    pub mod noise;
+    pub mod dmc;
+    pub mod units;
+    pub mod mixer;
+    pub mod resampler;
+    pub mod wav;
 }
 mod ppu;
+mod mappers {
+    pub mod mapper;
+    pub mod nrom;
+    pub mod mmc1;
+    pub mod uxrom;
+    pub mod cnrom;
+    pub mod axrom;
+    pub mod mmc3;
+}
+mod nes;
+mod controller;
 mod arguments;
 
 use arguments::ProgramArguments;
 use file_format::*;
+use disassembler::{disassemble, program_bank_address};
 
 #[cfg(test)]
 pub mod tests {
@@ -23,11 +52,36 @@ pub mod tests {
     pub mod cpu_test_stack;
     pub mod cpu_test_bit_bashing;
     pub mod cpu_test_misc;
+    pub mod cpu_test_interrupts;
+    pub mod cpu_test_arithmetic_logic;
+    pub mod cpu_test_cycles;
+    pub mod bus_test;
+    pub mod cpu_test_unofficial;
+    pub mod trace_test;
+    pub mod disassembler_test;
+    pub mod assembler_test;
+    pub mod ppu_test;
+    pub mod file_format_test;
+    pub mod nes_test;
+    pub mod mapper_test;
+    pub mod apu_test;
+    pub mod audio_test;
+    pub mod controller_test;
+    #[cfg(feature = "window")]
+    pub mod keyboard_test;
 }
 
 use std::path::Path;
 use integration_tests::*;
-use ppu::PPU;
+#[cfg(feature = "window")]
+use graphics::windows_display::WindowsDisplay;
+#[cfg(feature = "window")]
+use graphics::keyboard::KeyMapping;
+use cpu::IllegalOpcodeMode;
+use nes::{NES, SAVE_INTERVAL_FRAMES};
+use trace::TraceLogger;
+use audio::resampler::Resampler;
+use audio::wav::WavWriter;
 
 fn main() {
 
@@ -37,29 +91,188 @@ fn main() {
         return;
     }
 
-    let mut f = File::open("/Users/austinhaskell/Documents/roms/cpu_dummy_reads.nes").unwrap();
-    let mut buffer = Vec::new();
-
-    // read the whole file
-    f.read_to_end(&mut buffer).unwrap();
-
-    INES::from_bytes(buffer);
-
-    let mut ppu = PPU::new();
-
     let args = args.unwrap();
     if args.run_integration_tests {
-        let integration_bin_path: &Path     = Path::new("./integration_tests/");
+        let integration_source_path: &Path = Path::new("./integration_tests/");
         let output_dump_path: &Path = Path::new("./integration_tests_results/");
 
-        let err = run_integration_tests(integration_bin_path, true, output_dump_path);
+        let err = run_integration_tests(integration_source_path, true, output_dump_path);
         if err.is_err() {
             println!("{:?}", err.unwrap_err());
             println!("Failed to run integration tests. ");
         }
+        return;
+    }
+
+    if let Some(rom_path) = &args.disassemble_rom {
+        let rom = match INES::from_file(Path::new(rom_path)) {
+            Ok(rom) => rom,
+            Err(err) => {
+                println!("Failed to load [{}]: {}", rom_path, err);
+                return;
+            }
+        };
+
+        // A bank at a time, anything past 32KB gets switched in over the same addresses
+        let bank_count = rom.program_rom.len().div_ceil(PROGRAM_ROM_BANK_SIZE);
+        for (bank, bytes) in rom.program_rom.chunks(PROGRAM_ROM_BANK_SIZE).enumerate() {
+            let start_address = program_bank_address(bank, bank_count);
+            println!("; Bank {} at ${:04X}", bank, start_address);
+            for instruction in disassemble(bytes, start_address) {
+                println!("{}", instruction);
+            }
+        }
+        return;
+    }
+
+    let rom_path = match &args.rom_path {
+        Some(path) => path,
+        None => {
+            println!("No ROM given. See help screen for more info. ");
+            return;
+        }
+    };
+
+    let rom = match INES::from_file(Path::new(rom_path)) {
+        Ok(rom) => rom,
+        Err(err) => {
+            println!("Failed to load [{}]: {}", rom_path, err);
+            return;
+        }
+    };
+    println!("Loaded [{}]: {}", rom.title_text().unwrap_or_else(|| rom_path.clone()), rom.header);
+    if rom.play_choice_inst_rom.is_some() || rom.play_choice_prom.is_some() {
+        println!("PlayChoice-10 INST-ROM and PROM aren't emulated, only the NES side will run. ");
+    }
+
+    let mut nes = match NES::from_rom(&rom) {
+        Ok(nes) => nes,
+        Err(err) => {
+            println!("Failed to load [{}]: {}", rom_path, err);
+            return;
+        }
+    };
+
+    nes.cpu.illegal_opcode_mode = match args.illegal_opcode_mode.as_str() {
+        "log" => IllegalOpcodeMode::Log,
+        "trap" => IllegalOpcodeMode::Trap,
+        _ => IllegalOpcodeMode::Execute
+    };
+
+    if let Some(trace_path) = &args.trace_path {
+        match TraceLogger::to_file(trace_path) {
+            Ok(trace) => nes.cpu.trace = Some(trace),
+            Err(err) => println!("Could not create trace log [{}]: {}", trace_path, err)
+        }
+    }
+
+    nes.cpu.bus.apu.resampler = Resampler::new(args.sample_rate);
+    let mut audio_recording = match &args.record_audio_path {
+        Some(path) => match WavWriter::create(Path::new(path), args.sample_rate) {
+            Ok(writer) => Some(writer),
+            Err(err) => {
+                println!("Could not create audio recording [{}]: {}", path, err);
+                None
+            }
+        },
+        None => None
+    };
+
+    // Battery backed RAM lives next to the ROM, zelda.nes saves to zelda.sav
+    let save_path = Path::new(rom_path).with_extension("sav");
+    if nes.has_battery() {
+        if let Err(err) = nes.load_save(&save_path) {
+            println!("Could not load save [{}]: {}", save_path.display(), err);
+        }
+    }
+
+    let frame_limit = args.frames;
+    if args.headless {
+        run_headless(&mut nes, frame_limit, &mut audio_recording, &save_path);
+    }
+    else {
+        run_windowed(&mut nes, &args, frame_limit, &mut audio_recording, &save_path);
+    }
+
+    // A KIL, or an unofficial opcode in trap mode
+    if nes.cpu.halted {
+        println!("CPU halted at ${:04X}", nes.cpu.program_counter);
+    }
+
+    if nes.has_battery() && nes.has_unsaved_changes() {
+        write_save(&mut nes, &save_path);
+    }
+
+    if let Some(recording) = audio_recording {
+        if let Err(err) = recording.finish() {
+            println!("Could not finish audio recording: {}", err);
+        }
     }
+}
 
-    loop {
-        ppu.update();
+// No window and no input, for running on machines without a display
+fn run_headless(nes: &mut NES, frame_limit: Option<u64>, audio_recording: &mut Option<WavWriter>, save_path: &Path) {
+    let mut frames: u64 = 0;
+    while !nes.cpu.halted && frame_limit.is_none_or(|limit| frames < limit) {
+        nes.run_frame();
+        finish_frame(nes, audio_recording, save_path);
+        frames += 1;
     }
-}
\ No newline at end of file
+
+    println!("Ran {} frames", frames);
+}
+
+#[cfg(feature = "window")]
+fn run_windowed(nes: &mut NES, args: &ProgramArguments, frame_limit: Option<u64>, audio_recording: &mut Option<WavWriter>, save_path: &Path) {
+    let key_mapping = match &args.keymap_path {
+        Some(path) => match KeyMapping::from_file(Path::new(path)) {
+            Ok(mapping) => mapping,
+            Err(err) => {
+                println!("Could not load key mapping [{}], using the default: {}", path, err);
+                KeyMapping::new()
+            }
+        },
+        None => KeyMapping::new()
+    };
+
+    let mut frames: u64 = 0;
+    let mut display = WindowsDisplay::new();
+    while display.is_open() && !nes.cpu.halted && frame_limit.is_none_or(|limit| frames < limit) {
+        let buttons = key_mapping.buttons(&display.pressed_keys());
+        for (controller, buttons) in nes.cpu.bus.controllers.iter_mut().zip(buttons.iter()) {
+            controller.set_buttons(*buttons);
+        }
+
+        nes.run_frame();
+        display.update(nes.frame());
+        finish_frame(nes, audio_recording, save_path);
+        frames += 1;
+    }
+}
+
+#[cfg(not(feature = "window"))]
+fn run_windowed(nes: &mut NES, _args: &ProgramArguments, frame_limit: Option<u64>, audio_recording: &mut Option<WavWriter>, save_path: &Path) {
+    println!("Built without the window feature, running headless. ");
+    run_headless(nes, frame_limit, audio_recording, save_path);
+}
+
+// Whatever has to happen after every frame, window or not
+fn finish_frame(nes: &mut NES, audio_recording: &mut Option<WavWriter>, save_path: &Path) {
+    let samples = nes.audio_samples();
+    if let Some(recording) = audio_recording {
+        if let Err(err) = recording.write_samples(&samples) {
+            println!("Could not write audio recording: {}", err);
+            *audio_recording = None;
+        }
+    }
+
+    if nes.has_battery() && nes.has_unsaved_changes() && nes.cpu.bus.ppu.frame_count.is_multiple_of(SAVE_INTERVAL_FRAMES) {
+        write_save(nes, save_path);
+    }
+}
+
+fn write_save(nes: &mut NES, save_path: &Path) {
+    if let Err(err) = nes.write_save(save_path) {
+        println!("Could not write save [{}]: {}", save_path.display(), err);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::file_format::INES;
+use crate::mappers::mapper::{DiscreteBoard, Mapper};
+use crate::ppu::Mirroring;
+
+const PROGRAM_BANK_SIZE: usize = 0x8000;
+const SINGLE_SCREEN_UPPER: u8 = 0b0001_0000;
+
+// Mapper 7. Switches all 32KB of PRG at once and picks which nametable to show on 
+//  every screen, CHR is RAM.
+//  See https://www.nesdev.org/wiki/AxROM
+pub struct AxROM {
+    board: DiscreteBoard,
+    bank_select: u8
+}
+
+impl AxROM {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            // Only ANROM has bus conflicts, the header marks those boards with submapper 2
+            board: DiscreteBoard::new(rom, rom.header.submapper == 2),
+            bank_select: 0
+        }
+    }
+}
+
+impl Mapper for AxROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x8000 ..= 0xFFFF => self.board.program_read((self.bank_select & 0x07) as usize, PROGRAM_BANK_SIZE, addr),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if addr >= 0x8000 {
+            self.bank_select = self.board.bus_conflict(data, self.cpu_peek(addr));
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.board.chr_read(0, addr)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.board.chr_write(0, addr, data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        if self.bank_select & SINGLE_SCREEN_UPPER != 0 { Mirroring::SingleScreenUpper } else { Mirroring::SingleScreenLower }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::file_format::INES;
+use crate::mappers::mapper::{DiscreteBoard, Mapper};
+use crate::ppu::Mirroring;
+
+const PROGRAM_WINDOW_SIZE: usize = 0x8000;
+
+// Mapper 3. PRG is laid out the same as NROM, writes to $8000 - $FFFF pick an 8KB CHR bank.
+//  See https://www.nesdev.org/wiki/CNROM
+pub struct CNROM {
+    board: DiscreteBoard,
+    mirroring: Mirroring,
+    chr_bank: usize
+}
+
+impl CNROM {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            // Submapper 1 boards don't have bus conflicts
+            board: DiscreteBoard::new(rom, rom.header.submapper != 1),
+            mirroring: rom.header.mirroring,
+            chr_bank: 0
+        }
+    }
+}
+
+impl Mapper for CNROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x8000 ..= 0xFFFF => self.board.program_read(0, PROGRAM_WINDOW_SIZE, addr),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if addr >= 0x8000 {
+            self.chr_bank = self.board.bus_conflict(data, self.cpu_peek(addr)) as usize;
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.board.chr_read(self.chr_bank, addr)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.board.chr_write(self.chr_bank, addr, data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::file_format::{INES, RomError, PROGRAM_ROM_BANK_SIZE, CHAR_ROM_BANK_SIZE};
+use crate::mappers::nrom::NROM;
+use crate::mappers::mmc1::MMC1;
+use crate::mappers::uxrom::UxROM;
+use crate::mappers::cnrom::CNROM;
+use crate::mappers::axrom::AxROM;
+use crate::mappers::mmc3::MMC3;
+use crate::ppu::Mirroring;
+
+// Everything on the cartridge side of the edge connector. The CPU sees $4020 - $FFFF 
+//  and the PPU sees $0000 - $1FFF, what is actually behind those addresses (and which 
+//  bank of it) is up to the board. 
+pub trait Mapper {
+    // addr is anything from $4020 - $FFFF
+    fn cpu_read(&mut self, addr: u16) -> u8 {
+        self.cpu_peek(addr)
+    }
+
+    // Same as cpu_read but without any side effects, for the debugging tools
+    fn cpu_peek(&self, addr: u16) -> u8;
+    fn cpu_write(&mut self, addr: u16, data: u8);
+
+    // addr is anything from $0000 - $1FFF, the pattern tables
+    fn ppu_read(&self, addr: u16) -> u8;
+    fn ppu_write(&mut self, addr: u16, data: u8);
+
+    // Called with every address the PPU puts on its bus and the PPU cycle it happened 
+    //  on. Boards that count scanlines (MMC3) do it by watching A12.
+    fn ppu_address(&mut self, _addr: u16, _ppu_cycle: u64) {
+    }
+
+    // Called once for every CPU cycle, for boards that need to know how far apart 
+    //  writes were (MMC1)
+    fn cpu_cycle(&mut self) {
+    }
+
+    // Which nametables share memory, some boards can change this on the fly
+    fn mirroring(&self) -> Mirroring;
+
+    // Work RAM at $6000 - $7FFF, if the board has any. This is what gets saved when 
+    //  the cartridge has a battery.
+    fn program_ram(&self) -> Option<&[u8]> {
+        None
+    }
+
+    fn program_ram_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+
+    // True while the cartridge is holding the CPU's IRQ line low
+    fn irq(&self) -> bool {
+        false
+    }
+}
+
+// The CPU and PPU are both wired to the cartridge, so they share it
+pub type Cartridge = Rc<RefCell<dyn Mapper>>;
+
+// Picks the board for the mapper number in the header
+pub fn create_mapper(rom: &INES) -> Result<Cartridge, RomError> {
+    match rom.header.mapper {
+        0 => Ok(Rc::new(RefCell::new(NROM::new(rom)))),
+        1 => Ok(Rc::new(RefCell::new(MMC1::new(rom)))),
+        2 => Ok(Rc::new(RefCell::new(UxROM::new(rom)))),
+        3 => Ok(Rc::new(RefCell::new(CNROM::new(rom)))),
+        4 => Ok(Rc::new(RefCell::new(MMC3::new(rom)))),
+        7 => Ok(Rc::new(RefCell::new(AxROM::new(rom)))),
+        mapper => Err(RomError::UnsupportedMapper(mapper))
+    }
+}
+
+// PRG ROM and CHR as they come out of the image, every board keeps its banks in one of 
+//  these. CHR is 8KB of RAM when the image doesn't come with any. Indexes past the end 
+//  wrap around like the missing address lines would.
+pub struct CartridgeMemory {
+    program_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_is_ram: bool
+}
+
+impl CartridgeMemory {
+    pub fn new(rom: &INES) -> Self {
+        let mut program_rom = rom.program_rom.clone();
+        if program_rom.is_empty() {
+            program_rom.resize(PROGRAM_ROM_BANK_SIZE, 0);
+        }
+
+        let chr_is_ram = rom.char_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; CHAR_ROM_BANK_SIZE] } else { rom.char_rom.clone() };
+
+        Self {
+            program_rom,
+            chr,
+            chr_is_ram
+        }
+    }
+
+    pub fn program_len(&self) -> usize {
+        self.program_rom.len()
+    }
+
+    pub fn program_bank_count(&self, bank_size: usize) -> usize {
+        (self.program_rom.len() / bank_size).max(1)
+    }
+
+    pub fn program_read(&self, index: usize) -> u8 {
+        self.program_rom[index % self.program_rom.len()]
+    }
+
+    pub fn chr_read(&self, index: usize) -> u8 {
+        self.chr[index % self.chr.len()]
+    }
+
+    // Does nothing to CHR ROM
+    pub fn chr_write(&mut self, index: usize, data: u8) {
+        if self.chr_is_ram {
+            let index = index % self.chr.len();
+            self.chr[index] = data;
+        }
+    }
+}
+
+// The discrete logic boards (UxROM, CNROM, AxROM) only differ in how they bank the 
+//  cartridge memory, and whether they have bus conflicts.
+pub struct DiscreteBoard {
+    memory: CartridgeMemory,
+
+    // The bank latch is wired up to the same data bus as the ROM, so on a write they 
+    //  both drive it and the low bits win. Which boards have the problem depends on 
+    //  the mapper and submapper.
+    bus_conflicts: bool
+}
+
+impl DiscreteBoard {
+    pub fn new(rom: &INES, bus_conflicts: bool) -> Self {
+        Self {
+            memory: CartridgeMemory::new(rom),
+            bus_conflicts
+        }
+    }
+
+    pub fn program_bank_count(&self, bank_size: usize) -> usize {
+        self.memory.program_bank_count(bank_size)
+    }
+
+    // addr is the CPU address, bank_size has to be a power of two
+    pub fn program_read(&self, bank: usize, bank_size: usize, addr: u16) -> u8 {
+        self.memory.program_read(bank * bank_size + (addr as usize & (bank_size - 1)))
+    }
+
+    // CHR is always banked in 8KB here
+    pub fn chr_read(&self, bank: usize, addr: u16) -> u8 {
+        self.memory.chr_read(bank * CHAR_ROM_BANK_SIZE + addr as usize)
+    }
+
+    pub fn chr_write(&mut self, bank: usize, addr: u16, data: u8) {
+        self.memory.chr_write(bank * CHAR_ROM_BANK_SIZE + addr as usize, data);
+    }
+
+    // What the latch actually sees when the CPU writes data to addr, where rom_data is 
+    //  the byte the board has mapped there
+    pub fn bus_conflict(&self, data: u8, rom_data: u8) -> u8 {
+        if self.bus_conflicts { data & rom_data } else { data }
+    }
+}
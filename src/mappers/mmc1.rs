@@ -0,0 +1,179 @@
+use crate::file_format::INES;
+use crate::mappers::mapper::{Mapper, CartridgeMemory};
+use crate::mappers::nrom::{PROGRAM_RAM_START, PROGRAM_RAM_SIZE};
+use crate::ppu::Mirroring;
+
+const PROGRAM_BANK_SIZE: usize = 0x4000;
+const CHAR_BANK_SIZE: usize = 0x1000;
+
+// Boards with 512KB of PRG (SUROM) use a CHR bit to pick which 256KB half is visible
+const PROGRAM_OUTER_BANK_SIZE: usize = 0x40000;
+
+// Control register
+const CONTROL_MIRRORING: u8 = 0b0_0011;
+const CONTROL_PRG_MODE: u8  = 0b0_1100;
+const CONTROL_CHR_4K: u8    = 0b1_0000;
+
+const PRG_RAM_DISABLE: u8 = 0b1_0000;
+
+// Mapper 1. The registers are written one bit at a time through a 5 bit shift 
+//  register, the fifth write picks which register gets the value from its address.
+//  See https://www.nesdev.org/wiki/MMC1
+pub struct MMC1 {
+    memory: CartridgeMemory,
+    program_ram: [u8; PROGRAM_RAM_SIZE],
+
+    shift_register: u8,
+    shift_count: u8,
+
+    // The serial port ignores a write on the cycle straight after another one. Read 
+    //  modify write instructions write twice in a row, and games like Bill & Ted use 
+    //  INC $8000 to reset the shift register counting on the second write being dropped.
+    cycles: u64,
+    last_serial_write: Option<u64>,
+
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    program_bank: u8
+}
+
+impl MMC1 {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            memory: CartridgeMemory::new(rom),
+            program_ram: [0; PROGRAM_RAM_SIZE],
+            shift_register: 0,
+            shift_count: 0,
+            cycles: 0,
+            last_serial_write: None,
+            // Powers on with the last bank fixed at $C000 so the reset vector is somewhere sensible
+            control: CONTROL_PRG_MODE,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            program_bank: 0
+        }
+    }
+
+    fn write_register(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x8000 ..= 0x9FFF => self.control = data,
+            0xA000 ..= 0xBFFF => self.chr_bank_0 = data,
+            0xC000 ..= 0xDFFF => self.chr_bank_1 = data,
+            _ => self.program_bank = data
+        }
+    }
+
+    fn program_ram_enabled(&self) -> bool {
+        self.program_bank & PRG_RAM_DISABLE == 0
+    }
+
+    fn program_rom_index(&self, addr: u16) -> usize {
+        let outer_bank = if self.memory.program_len() > PROGRAM_OUTER_BANK_SIZE { (self.chr_bank_0 & 0x10) as usize >> 4 } else { 0 };
+        let banks_per_outer = PROGRAM_OUTER_BANK_SIZE / PROGRAM_BANK_SIZE;
+        let last_bank = self.memory.program_bank_count(PROGRAM_BANK_SIZE).min(banks_per_outer) - 1;
+        let selected = (self.program_bank & 0x0F) as usize;
+
+        let bank = match ((self.control & CONTROL_PRG_MODE) >> 2, addr) {
+            // 32KB at a time, the low bit of the bank number is ignored
+            (0, 0x8000 ..= 0xBFFF) | (1, 0x8000 ..= 0xBFFF) => selected & !1,
+            (0, _) | (1, _) => selected | 1,
+            // First bank fixed at $8000
+            (2, 0x8000 ..= 0xBFFF) => 0,
+            (2, _) => selected,
+            // Last bank fixed at $C000
+            (_, 0x8000 ..= 0xBFFF) => selected,
+            (_, _) => last_bank
+        };
+
+        (outer_bank * banks_per_outer + bank) * PROGRAM_BANK_SIZE + (addr as usize & (PROGRAM_BANK_SIZE - 1))
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = if self.control & CONTROL_CHR_4K != 0 {
+            (if addr < 0x1000 { self.chr_bank_0 } else { self.chr_bank_1 }) as usize
+        }
+        else {
+            // 8KB at a time, the low bit of the bank number is ignored
+            (self.chr_bank_0 & !1) as usize + (addr as usize / CHAR_BANK_SIZE)
+        };
+
+        bank * CHAR_BANK_SIZE + (addr as usize & (CHAR_BANK_SIZE - 1))
+    }
+}
+
+impl Mapper for MMC1 {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PROGRAM_RAM_START ..= 0x7FFF if self.program_ram_enabled() => self.program_ram[(addr - PROGRAM_RAM_START) as usize],
+            0x8000 ..= 0xFFFF => self.memory.program_read(self.program_rom_index(addr)),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            PROGRAM_RAM_START ..= 0x7FFF if self.program_ram_enabled() => {
+                self.program_ram[(addr - PROGRAM_RAM_START) as usize] = data;
+            },
+            0x8000 ..= 0xFFFF => {
+                // The CPU only writes on back to back cycles during a read modify write, 
+                //  where the dummy write of the old value (load_for_modify) comes straight 
+                //  before the real one. That dummy write is the one that counts.
+                let consecutive = self.last_serial_write.is_some_and(|cycle| self.cycles - cycle <= 1);
+                self.last_serial_write = Some(self.cycles);
+                if consecutive {
+                    return;
+                }
+
+                // Bit 7 resets the shift register and goes back to the power on PRG mode
+                if data & 0x80 != 0 {
+                    self.shift_register = 0;
+                    self.shift_count = 0;
+                    self.control |= CONTROL_PRG_MODE;
+                    return;
+                }
+
+                // Bits come in lowest first
+                self.shift_register |= (data & 0x01) << self.shift_count;
+                self.shift_count += 1;
+
+                if self.shift_count == 5 {
+                    self.write_register(addr, self.shift_register);
+                    self.shift_register = 0;
+                    self.shift_count = 0;
+                }
+            },
+            _ => { }
+        }
+    }
+
+    fn cpu_cycle(&mut self) {
+        self.cycles += 1;
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.memory.chr_read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.memory.chr_write(self.chr_index(addr), data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & CONTROL_MIRRORING {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal
+        }
+    }
+
+    fn program_ram(&self) -> Option<&[u8]> {
+        Some(&self.program_ram)
+    }
+
+    fn program_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.program_ram)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::file_format::INES;
+use crate::mappers::mapper::{Mapper, CartridgeMemory};
+use crate::mappers::nrom::{PROGRAM_RAM_START, PROGRAM_RAM_SIZE};
+use crate::ppu::Mirroring;
+
+const PROGRAM_BANK_SIZE: usize = 0x2000;
+const CHAR_BANK_SIZE: usize = 0x0400;
+
+// Bank select ($8000)
+const BANK_SELECT_REGISTER: u8  = 0b0000_0111;
+const BANK_SELECT_PRG_MODE: u8  = 0b0100_0000;
+const BANK_SELECT_CHR_INVERT: u8 = 0b1000_0000;
+
+// PRG RAM protect ($A001)
+const PRG_RAM_ENABLE: u8        = 0b1000_0000;
+const PRG_RAM_WRITE_PROTECT: u8 = 0b0100_0000;
+
+const A12: u16 = 0x1000;
+
+// A12 has to have been low for about 3 M2 cycles before a rise counts, otherwise the 
+//  8x16 sprite fetches would clock the counter several times a line. The PPU reports 
+//  its position in dots, 3 to a CPU cycle, so that's 9 or 10 of them.
+const A12_FILTER_DOTS: u64 = 10;
+
+// Mapper 4. 8KB PRG banks, 1KB and 2KB CHR banks, and a counter that gets clocked 
+//  once per scanline by A12 going high when the PPU moves from one pattern table to the 
+//  other, which lets games raise an IRQ part of the way down the screen.
+//  See https://www.nesdev.org/wiki/MMC3
+pub struct MMC3 {
+    memory: CartridgeMemory,
+    program_ram: [u8; PROGRAM_RAM_SIZE],
+
+    bank_select: u8,
+    // R0 - R7
+    bank_registers: [u8; 8],
+    mirroring: Mirroring,
+    four_screen: bool,
+    program_ram_protect: u8,
+
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+
+    // PPU cycle A12 last went low, None while it is high
+    a12_low_since: Option<u64>
+}
+
+impl MMC3 {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            memory: CartridgeMemory::new(rom),
+            program_ram: [0; PROGRAM_RAM_SIZE],
+            bank_select: 0,
+            bank_registers: [0; 8],
+            mirroring: rom.header.mirroring,
+            four_screen: rom.header.four_screen,
+            program_ram_protect: PRG_RAM_ENABLE,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+            a12_low_since: Some(0)
+        }
+    }
+
+    fn write_register(&mut self, addr: u16, data: u8) {
+        let even = addr & 0x01 == 0;
+        match (addr, even) {
+            (0x8000 ..= 0x9FFF, true) => self.bank_select = data,
+            (0x8000 ..= 0x9FFF, false) => self.bank_registers[(self.bank_select & BANK_SELECT_REGISTER) as usize] = data,
+            (0xA000 ..= 0xBFFF, true) => {
+                // Four screen boards have their own nametable RAM wired up instead
+                if !self.four_screen {
+                    self.mirroring = if data & 0x01 != 0 { Mirroring::Horizontal } else { Mirroring::Vertical };
+                }
+            },
+            (0xA000 ..= 0xBFFF, false) => self.program_ram_protect = data,
+            (0xC000 ..= 0xDFFF, true) => self.irq_latch = data,
+            (0xC000 ..= 0xDFFF, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            },
+            (_, true) => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            },
+            (_, false) => self.irq_enabled = true
+        }
+    }
+
+    // Reloads when it hits zero (or has been told to), then raises the IRQ if it is zero
+    fn clock_irq_counter(&mut self) {
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        }
+        else {
+            self.irq_counter -= 1;
+        }
+
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+
+    fn program_rom_index(&self, addr: u16) -> usize {
+        let bank_count = self.memory.program_bank_count(PROGRAM_BANK_SIZE);
+        let second_last = bank_count.saturating_sub(2);
+        let swapped = self.bank_select & BANK_SELECT_PRG_MODE != 0;
+
+        let bank = match (addr, swapped) {
+            (0x8000 ..= 0x9FFF, false) | (0xC000 ..= 0xDFFF, true) => self.bank_registers[6] as usize & 0x3F,
+            (0x8000 ..= 0x9FFF, true) | (0xC000 ..= 0xDFFF, false) => second_last,
+            (0xA000 ..= 0xBFFF, _) => self.bank_registers[7] as usize & 0x3F,
+            _ => bank_count - 1
+        };
+
+        bank * PROGRAM_BANK_SIZE + (addr as usize & (PROGRAM_BANK_SIZE - 1))
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        // The inversion bit swaps which half gets the 2KB banks
+        let addr = if self.bank_select & BANK_SELECT_CHR_INVERT != 0 { addr ^ 0x1000 } else { addr };
+
+        let bank = match addr {
+            0x0000 ..= 0x03FF => self.bank_registers[0] & 0xFE,
+            0x0400 ..= 0x07FF => self.bank_registers[0] | 0x01,
+            0x0800 ..= 0x0BFF => self.bank_registers[1] & 0xFE,
+            0x0C00 ..= 0x0FFF => self.bank_registers[1] | 0x01,
+            0x1000 ..= 0x13FF => self.bank_registers[2],
+            0x1400 ..= 0x17FF => self.bank_registers[3],
+            0x1800 ..= 0x1BFF => self.bank_registers[4],
+            _ => self.bank_registers[5]
+        } as usize;
+
+        bank * CHAR_BANK_SIZE + (addr as usize & (CHAR_BANK_SIZE - 1))
+    }
+}
+
+impl Mapper for MMC3 {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PROGRAM_RAM_START ..= 0x7FFF if self.program_ram_protect & PRG_RAM_ENABLE != 0 => {
+                self.program_ram[(addr - PROGRAM_RAM_START) as usize]
+            },
+            0x8000 ..= 0xFFFF => self.memory.program_read(self.program_rom_index(addr)),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            PROGRAM_RAM_START ..= 0x7FFF if self.program_ram_protect & (PRG_RAM_ENABLE | PRG_RAM_WRITE_PROTECT) == PRG_RAM_ENABLE => {
+                self.program_ram[(addr - PROGRAM_RAM_START) as usize] = data;
+            },
+            0x8000 ..= 0xFFFF => self.write_register(addr, data),
+            _ => { }
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.memory.chr_read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.memory.chr_write(self.chr_index(addr), data);
+    }
+
+    fn ppu_address(&mut self, addr: u16, ppu_cycle: u64) {
+        if addr & A12 == 0 {
+            if self.a12_low_since.is_none() {
+                self.a12_low_since = Some(ppu_cycle);
+            }
+            return;
+        }
+
+        if let Some(low_since) = self.a12_low_since.take() {
+            if ppu_cycle - low_since >= A12_FILTER_DOTS {
+                self.clock_irq_counter();
+            }
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn program_ram(&self) -> Option<&[u8]> {
+        Some(&self.program_ram)
+    }
+
+    fn program_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.program_ram)
+    }
+
+    fn irq(&self) -> bool {
+        self.irq_pending
+    }
+}
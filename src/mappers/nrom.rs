@@ -0,0 +1,67 @@
+use crate::file_format::INES;
+use crate::mappers::mapper::{Mapper, CartridgeMemory};
+use crate::ppu::Mirroring;
+
+pub const PROGRAM_RAM_START: u16 = 0x6000;
+pub const PROGRAM_RAM_SIZE: usize = 0x2000;
+pub const PROGRAM_ROM_START: u16 = 0x8000;
+
+// Mapper 0, no bank switching at all. NROM-256 has 32KB of PRG filling $8000 - $FFFF, 
+//  NROM-128 only has 16KB so it shows up twice. 
+//  See https://www.nesdev.org/wiki/NROM
+pub struct NROM {
+    memory: CartridgeMemory,
+
+    // Only Family Basic actually has this, but it doesn't hurt anything else and 
+    //  trainers need somewhere to live
+    program_ram: [u8; PROGRAM_RAM_SIZE],
+
+    mirroring: Mirroring
+}
+
+impl NROM {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            memory: CartridgeMemory::new(rom),
+            program_ram: [0; PROGRAM_RAM_SIZE],
+            mirroring: rom.header.mirroring
+        }
+    }
+}
+
+impl Mapper for NROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PROGRAM_RAM_START ..= 0x7FFF => self.program_ram[(addr - PROGRAM_RAM_START) as usize],
+            PROGRAM_ROM_START ..= 0xFFFF => self.memory.program_read((addr - PROGRAM_ROM_START) as usize),
+            // Nothing is connected down here
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if let PROGRAM_RAM_START ..= 0x7FFF = addr {
+            self.program_ram[(addr - PROGRAM_RAM_START) as usize] = data;
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.memory.chr_read(addr as usize)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.memory.chr_write(addr as usize, data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn program_ram(&self) -> Option<&[u8]> {
+        Some(&self.program_ram)
+    }
+
+    fn program_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.program_ram)
+    }
+}
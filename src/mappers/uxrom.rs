@@ -0,0 +1,53 @@
+use crate::file_format::{INES, PROGRAM_ROM_BANK_SIZE};
+use crate::mappers::mapper::{DiscreteBoard, Mapper};
+use crate::ppu::Mirroring;
+
+// Mapper 2. A switchable 16KB bank at $8000 and the last bank fixed at $C000, 
+//  CHR is almost always RAM. 
+//  See https://www.nesdev.org/wiki/UxROM
+pub struct UxROM {
+    board: DiscreteBoard,
+    mirroring: Mirroring,
+    program_bank: usize
+}
+
+impl UxROM {
+    pub fn new(rom: &INES) -> Self {
+        Self {
+            // Submapper 1 boards don't have bus conflicts
+            board: DiscreteBoard::new(rom, rom.header.submapper != 1),
+            mirroring: rom.header.mirroring,
+            program_bank: 0
+        }
+    }
+}
+
+impl Mapper for UxROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        let bank = match addr {
+            0x8000 ..= 0xBFFF => self.program_bank,
+            0xC000 ..= 0xFFFF => self.board.program_bank_count(PROGRAM_ROM_BANK_SIZE) - 1,
+            _ => return 0
+        };
+
+        self.board.program_read(bank, PROGRAM_ROM_BANK_SIZE, addr)
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if addr >= 0x8000 {
+            self.program_bank = self.board.bus_conflict(data, self.cpu_peek(addr)) as usize;
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> u8 {
+        self.board.chr_read(0, addr)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.board.chr_write(0, addr, data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
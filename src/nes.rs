@@ -0,0 +1,92 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::bus::NESBus;
+use crate::cpu::CPU;
+use crate::file_format::{INES, RomError};
+
+// How often the battery backed RAM gets written out while running, about every 30 seconds
+pub const SAVE_INTERVAL_FRAMES: u64 = 60 * 30;
+
+// 341 PPU dots at 3 a CPU cycle, rounded up
+pub const CYCLES_PER_SCANLINE: u64 = 114;
+
+// The whole console, a CPU with a cartridge plugged into its bus
+pub struct NES {
+    pub cpu: CPU<NESBus>,
+    has_battery: bool
+}
+
+impl NES {
+    pub fn from_rom(rom: &INES) -> Result<Self, RomError> {
+        let mut bus = NESBus::new();
+        bus.load_rom(rom)?;
+
+        Ok(Self {
+            cpu: CPU::with_bus(bus),
+            has_battery: rom.header.battery
+        })
+    }
+
+    // Runs until the PPU has finished drawing a frame. It goes a scanline at a time, 
+    //  the frame is done at the start of vblank so going a little past it doesn't 
+    //  touch the picture.
+    pub fn run_frame(&mut self) {
+        self.cpu.bus.ppu.frame_complete = false;
+        while !self.cpu.bus.ppu.frame_complete && !self.cpu.halted {
+            self.cpu.run_for_cycles(CYCLES_PER_SCANLINE);
+        }
+    }
+
+    // One NES palette index per pixel
+    #[cfg(any(feature = "window", test))]
+    pub fn frame(&self) -> &[u8] {
+        &self.cpu.bus.ppu.frame
+    }
+
+    // Audio played since the last call, see Resampler for the format
+    pub fn audio_samples(&mut self) -> Vec<f32> {
+        self.cpu.bus.apu.take_samples()
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.has_battery
+    }
+
+    // Fills $6000 - $7FFF from a .sav file. Not having one yet is fine, that just 
+    //  means the game has never been saved.
+    pub fn load_save(&mut self, path: &Path) -> io::Result<()> {
+        let save = match fs::read(path) {
+            Ok(save) => save,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err)
+        };
+
+        if let Some(cartridge) = self.cpu.bus.cartridge() {
+            if let Some(ram) = cartridge.borrow_mut().program_ram_mut() {
+                let length = ram.len().min(save.len());
+                ram[..length].copy_from_slice(&save[..length]);
+            }
+        }
+
+        Ok(())
+    }
+
+    // True if the game has written to $6000 - $7FFF since the last save
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.cpu.bus.program_ram_written
+    }
+
+    // Writes $6000 - $7FFF out as is, which is the same layout other emulators use
+    pub fn write_save(&mut self, path: &Path) -> io::Result<()> {
+        if let Some(cartridge) = self.cpu.bus.cartridge() {
+            if let Some(ram) = cartridge.borrow().program_ram() {
+                fs::write(path, ram)?;
+            }
+        }
+
+        self.cpu.bus.program_ram_written = false;
+        Ok(())
+    }
+}
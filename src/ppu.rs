@@ -1,35 +1,710 @@
-use minifb::*;
+use crate::mappers::mapper::Cartridge;
 
-const WIDTH: usize = 256;
-const HEIGHT: usize = 240;
+pub const PPUCTRL: u16   = 0x2000;
+pub const PPUMASK: u16   = 0x2001;
+pub const PPUSTATUS: u16 = 0x2002;
+pub const OAMADDR: u16   = 0x2003;
+pub const OAMDATA: u16   = 0x2004;
+pub const PPUSCROLL: u16 = 0x2005;
+pub const PPUADDR: u16   = 0x2006;
+pub const PPUDATA: u16   = 0x2007;
 
+// PPUCTRL
+pub const CTRL_NAMETABLE: u8          = 0b0000_0011;
+pub const CTRL_VRAM_INCREMENT: u8     = 0b0000_0100;
+pub const CTRL_SPRITE_TABLE: u8       = 0b0000_1000;
+pub const CTRL_BACKGROUND_TABLE: u8   = 0b0001_0000;
+pub const CTRL_SPRITE_SIZE: u8        = 0b0010_0000;
+pub const CTRL_NMI_ENABLE: u8         = 0b1000_0000;
+
+// PPUMASK
+pub const MASK_GREYSCALE: u8          = 0b0000_0001;
+pub const MASK_SHOW_BACKGROUND_LEFT: u8 = 0b0000_0010;
+pub const MASK_SHOW_SPRITES_LEFT: u8  = 0b0000_0100;
+pub const MASK_SHOW_BACKGROUND: u8    = 0b0000_1000;
+pub const MASK_SHOW_SPRITES: u8       = 0b0001_0000;
+
+// PPUSTATUS
+pub const STATUS_SPRITE_OVERFLOW: u8  = 0b0010_0000;
+pub const STATUS_SPRITE_ZERO_HIT: u8  = 0b0100_0000;
+pub const STATUS_VBLANK: u8           = 0b1000_0000;
+
+pub const PATTERN_TABLE_SIZE: usize   = 0x2000;
+pub const NAMETABLE_SIZE: usize       = 0x0400;
+pub const VRAM_SIZE: usize            = 0x0800;
+pub const PALETTE_SIZE: usize         = 0x20;
+pub const OAM_SIZE: usize             = 0x100;
+pub const SPRITES_PER_SCANLINE: usize = 8;
+
+// Sprite attribute byte
+pub const SPRITE_PALETTE: u8          = 0b0000_0011;
+pub const SPRITE_BEHIND_BACKGROUND: u8 = 0b0010_0000;
+pub const SPRITE_FLIP_HORIZONTAL: u8  = 0b0100_0000;
+pub const SPRITE_FLIP_VERTICAL: u8    = 0b1000_0000;
+
+pub const FRAME_WIDTH: usize          = 256;
+pub const FRAME_HEIGHT: usize         = 240;
+
+pub const DOTS_PER_SCANLINE: u16      = 341;
+pub const VBLANK_SCANLINE: u16        = 241;
+pub const PRE_RENDER_SCANLINE: u16    = 261;
+
+pub const NAMETABLE_START: u16        = 0x2000;
+pub const ATTRIBUTE_TABLE_START: u16  = 0x23C0;
+pub const NAMETABLE_MIRROR_END: u16   = 0x3EFF;
+pub const PALETTE_START: u16          = 0x3F00;
+pub const SPRITE_PALETTE_START: u16   = 0x3F10;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mirroring {
+	Horizontal,
+	Vertical,
+	SingleScreenLower,
+	SingleScreenUpper,
+	FourScreen
+}
+
+// The picture processing unit, as seen through its eight registers at $2000 - $2007.
+//
+// The PPU has its own 16KB address space:
+//  $0000 - $1FFF : Pattern tables (CHR on the cartridge)
+//  $2000 - $2FFF : Nametables, 2KB of VRAM mirrored according to the cartridge
+//  $3000 - $3EFF : Mirror of $2000 - $2EFF
+//  $3F00 - $3FFF : Palette RAM, 32 bytes mirrored
 pub struct PPU {
-	window: Window
+	pub ctrl: u8,
+	pub mask: u8,
+	pub status: u8,
+	pub oam_addr: u8,
+
+	// Internal registers, named the way the nesdev wiki does
+	//  v : Current VRAM address (15 bits)
+	//  t : Temporary VRAM address, also the top left of the screen
+	//  x : Fine X scroll (3 bits)
+	//  w : First or second write toggle for $2005 and $2006
+	pub v: u16,
+	pub t: u16,
+	pub x: u8,
+	pub w: bool,
+
+	// PPUDATA reads come out one read late, except for the palette
+	read_buffer: u8,
+
+	// Reading a write only register gives back whatever was last on the data bus
+	data_bus: u8,
+
+	// The cartridge supplies the pattern tables and decides the mirroring, without one 
+	//  these are used instead
+	pub cartridge: Option<Cartridge>,
+	pub chr: Vec<u8>,
+	pub vram: [u8; VRAM_SIZE],
+	// Four screen boards carry another 2KB so every nametable gets its own memory
+	pub four_screen_vram: [u8; VRAM_SIZE],
+	pub palette: [u8; PALETTE_SIZE],
+	pub oam: [u8; OAM_SIZE],
+	pub mirroring: Mirroring,
+
+	// Where the PPU is in the frame. Scanlines 0 - 239 are visible, 240 is idle, 241 - 260 
+	//  are vblank and 261 is the pre-render line that gets everything ready for the next frame.
+	pub scanline: u16,
+	pub dot: u16,
+
+	// One NES palette index (0 - 63) per pixel
+	pub frame: Vec<u8>,
+	pub frame_complete: bool,
+	pub frame_count: u64,
+	odd_frame: bool,
+
+	// Dots since power on, so the cartridge can tell how far apart two fetches were
+	cycles: u64,
+	nmi_pending: bool,
+
+	// Tile data fetched for the next tile, loaded into the shifters every 8 dots
+	bg_next_tile_id: u8,
+	bg_next_attribute: u8,
+	bg_next_pattern_low: u8,
+	bg_next_pattern_high: u8,
+
+	// The high byte is the tile being drawn, the low byte is the one after it
+	bg_shifter_pattern_low: u16,
+	bg_shifter_pattern_high: u16,
+	bg_shifter_attribute_low: u16,
+	bg_shifter_attribute_high: u16,
+
+	// The sprites found for the scanline being drawn, with their pattern bytes already
+	//  flipped so the leftmost pixel is always bit 7
+	sprite_count: usize,
+	sprite_patterns_low: [u8; SPRITES_PER_SCANLINE],
+	sprite_patterns_high: [u8; SPRITES_PER_SCANLINE],
+	sprite_positions: [u8; SPRITES_PER_SCANLINE],
+	sprite_attributes: [u8; SPRITES_PER_SCANLINE],
+	sprite_zero_on_line: bool
 }
 
 impl PPU {
 	pub fn new() -> Self {
-		let options = WindowOptions {
-		        scale: Scale::X2,
-		        ..WindowOptions::default()
-		    };
-	    let mut window = Window::new(
-		        "Blit Example - ESC to exit & click to draw",
-		        HEIGHT,
-		        WIDTH,
-		        options,
-		    )
-		    .expect("Unable to open window");
-
 		Self {
-			window: window
+			ctrl: 0,
+			mask: 0,
+			status: 0,
+			oam_addr: 0,
+			v: 0,
+			t: 0,
+			x: 0,
+			w: false,
+			read_buffer: 0,
+			data_bus: 0,
+			cartridge: None,
+			chr: vec![0; PATTERN_TABLE_SIZE],
+			vram: [0; VRAM_SIZE],
+			four_screen_vram: [0; VRAM_SIZE],
+			palette: [0; PALETTE_SIZE],
+			oam: [0; OAM_SIZE],
+			mirroring: Mirroring::Horizontal,
+			scanline: 0,
+			dot: 0,
+			frame: vec![0; FRAME_WIDTH * FRAME_HEIGHT],
+			frame_complete: false,
+			frame_count: 0,
+			odd_frame: false,
+			cycles: 0,
+			nmi_pending: false,
+			bg_next_tile_id: 0,
+			bg_next_attribute: 0,
+			bg_next_pattern_low: 0,
+			bg_next_pattern_high: 0,
+			bg_shifter_pattern_low: 0,
+			bg_shifter_pattern_high: 0,
+			bg_shifter_attribute_low: 0,
+			bg_shifter_attribute_high: 0,
+			sprite_count: 0,
+			sprite_patterns_low: [0; SPRITES_PER_SCANLINE],
+			sprite_patterns_high: [0; SPRITES_PER_SCANLINE],
+			sprite_positions: [0; SPRITES_PER_SCANLINE],
+			sprite_attributes: [0; SPRITES_PER_SCANLINE],
+			sprite_zero_on_line: false
+		}
+	}
+
+	// True once for every time the PPU wants an NMI
+	pub fn poll_nmi(&mut self) -> bool {
+		let pending = self.nmi_pending;
+		self.nmi_pending = false;
+		pending
+	}
+
+	pub fn is_rendering_enabled(&self) -> bool {
+		self.mask & (MASK_SHOW_BACKGROUND | MASK_SHOW_SPRITES) != 0
+	}
+
+	// Runs the PPU for a single dot. The CPU gets three of these per cycle.
+	pub fn tick(&mut self) {
+		let rendering = self.is_rendering_enabled();
+		let is_render_line = self.scanline < FRAME_HEIGHT as u16 || self.scanline == PRE_RENDER_SCANLINE;
+
+		if self.scanline == PRE_RENDER_SCANLINE && self.dot == 1 {
+			self.status &= !(STATUS_VBLANK | STATUS_SPRITE_ZERO_HIT | STATUS_SPRITE_OVERFLOW);
+		}
+
+		if is_render_line && rendering {
+			self.run_background_pipeline();
+
+			if self.dot == 257 {
+				self.evaluate_sprites();
+			}
+		}
+
+		if self.scanline == VBLANK_SCANLINE && self.dot == 1 {
+			self.status |= STATUS_VBLANK;
+			if self.ctrl & CTRL_NMI_ENABLE != 0 {
+				self.nmi_pending = true;
+			}
+		}
+
+		if self.scanline < FRAME_HEIGHT as u16 && self.dot >= 1 && self.dot <= FRAME_WIDTH as u16 {
+			self.draw_pixel();
+		}
+
+		self.dot += 1;
+		self.cycles += 1;
+
+		// Odd frames are one dot shorter when rendering, the idle dot at the start of
+		//  scanline 0 gets skipped
+		if self.scanline == PRE_RENDER_SCANLINE && self.dot == DOTS_PER_SCANLINE - 1 && self.odd_frame && rendering {
+			self.dot = DOTS_PER_SCANLINE;
 		}
+
+		if self.dot >= DOTS_PER_SCANLINE {
+			self.dot = 0;
+			self.scanline += 1;
+
+			if self.scanline > PRE_RENDER_SCANLINE {
+				self.scanline = 0;
+				self.frame_complete = true;
+				self.frame_count += 1;
+				self.odd_frame = !self.odd_frame;
+			}
+		}
+	}
+
+	// The background fetches repeat every 8 dots: nametable byte, attribute byte, then the
+	//  low and high pattern bytes, two dots each. Dots 321 - 336 prefetch the first two 
+	//  tiles of the next line.
+	fn run_background_pipeline(&mut self) {
+		let dot = self.dot;
+
+		if (2..=257).contains(&dot) || (321..=337).contains(&dot) {
+			self.shift_background();
+
+			match (dot - 1) % 8 {
+				0 => {
+					self.load_background_shifters();
+					self.bg_next_tile_id = self.read(NAMETABLE_START | (self.v & 0x0FFF));
+				},
+				2 => {
+					let v = self.v;
+					let attribute = self.read(ATTRIBUTE_TABLE_START | (v & 0x0C00) | ((v >> 4) & 0x38) | ((v >> 2) & 0x07));
+
+					// Each attribute byte covers a 4x4 tile area, two bits per 2x2 quadrant
+					let shift = ((v >> 4) & 0x04) | (v & 0x02);
+					self.bg_next_attribute = (attribute >> shift) & 0x03;
+				},
+				4 => self.bg_next_pattern_low = self.read(self.background_pattern_addr()),
+				6 => self.bg_next_pattern_high = self.read(self.background_pattern_addr() + 8),
+				7 => self.increment_scroll_x(),
+				_ => { }
+			}
+		}
+
+		if dot == 256 {
+			self.increment_scroll_y();
+		}
+
+		if dot == 257 {
+			self.load_background_shifters();
+			self.transfer_address_x();
+		}
+
+		// Unused nametable fetches, some mappers watch for these
+		if dot == 338 || dot == 340 {
+			self.bg_next_tile_id = self.read(NAMETABLE_START | (self.v & 0x0FFF));
+		}
+
+		if self.scanline == PRE_RENDER_SCANLINE && (280..=304).contains(&dot) {
+			self.transfer_address_y();
+		}
+	}
+
+	fn background_pattern_addr(&self) -> u16 {
+		let table: u16 = if self.ctrl & CTRL_BACKGROUND_TABLE != 0 { 0x1000 } else { 0x0000 };
+		let fine_y = (self.v >> 12) & 0x07;
+
+		table + (self.bg_next_tile_id as u16) * 16 + fine_y
 	}
 
-	pub fn update(self: &mut Self) {
-		let mut buffer: Vec<u32> = vec![0x00_FF_FF_FF; WIDTH * HEIGHT];
+	fn load_background_shifters(&mut self) {
+		self.bg_shifter_pattern_low = (self.bg_shifter_pattern_low & 0xFF00) | self.bg_next_pattern_low as u16;
+		self.bg_shifter_pattern_high = (self.bg_shifter_pattern_high & 0xFF00) | self.bg_next_pattern_high as u16;
 
-		self.window.update_with_buffer(&buffer, WIDTH, HEIGHT).unwrap();
+		// The attribute shifters get the same palette for all 8 pixels of the tile
+		let attribute_low = if self.bg_next_attribute & 0x01 != 0 { 0x00FF } else { 0x0000 };
+		let attribute_high = if self.bg_next_attribute & 0x02 != 0 { 0x00FF } else { 0x0000 };
+		self.bg_shifter_attribute_low = (self.bg_shifter_attribute_low & 0xFF00) | attribute_low;
+		self.bg_shifter_attribute_high = (self.bg_shifter_attribute_high & 0xFF00) | attribute_high;
 	}
-		
-}
\ No newline at end of file
+
+	fn shift_background(&mut self) {
+		self.bg_shifter_pattern_low <<= 1;
+		self.bg_shifter_pattern_high <<= 1;
+		self.bg_shifter_attribute_low <<= 1;
+		self.bg_shifter_attribute_high <<= 1;
+	}
+
+	// Returns the pixel (0 - 3) and palette (0 - 3) for the background at the current dot
+	fn background_pixel(&self) -> (u8, u8) {
+		if self.mask & MASK_SHOW_BACKGROUND == 0 {
+			return (0, 0);
+		}
+		if self.dot <= 8 && self.mask & MASK_SHOW_BACKGROUND_LEFT == 0 {
+			return (0, 0);
+		}
+
+		let bit = 0x8000 >> self.x;
+		let pixel = ((self.bg_shifter_pattern_low & bit != 0) as u8) | ((self.bg_shifter_pattern_high & bit != 0) as u8) << 1;
+		let palette = ((self.bg_shifter_attribute_low & bit != 0) as u8) | ((self.bg_shifter_attribute_high & bit != 0) as u8) << 1;
+
+		(pixel, palette)
+	}
+
+	// Real hardware spreads this over dots 65 - 320, but nothing can see the difference
+	//  apart from the OAM address being reset. Sprites found here are drawn on the next 
+	//  scanline, which is why a sprite's Y position in OAM is one less than where it shows up.
+	fn evaluate_sprites(&mut self) {
+		self.oam_addr = 0;
+		self.sprite_count = 0;
+		self.sprite_zero_on_line = false;
+
+		let height: u16 = if self.ctrl & CTRL_SPRITE_SIZE != 0 { 16 } else { 8 };
+		let in_range = |y: u8| self.scanline >= y as u16 && self.scanline - (y as u16) < height;
+
+		let mut found: Vec<usize> = Vec::with_capacity(SPRITES_PER_SCANLINE);
+
+		// Nothing is ever drawn from the pre-render line's sprites
+		if self.scanline != PRE_RENDER_SCANLINE {
+			let mut n = 0;
+			while n < 64 && found.len() < SPRITES_PER_SCANLINE {
+				if in_range(self.oam[n * 4]) {
+					found.push(n);
+				}
+				n += 1;
+			}
+
+			// Once secondary OAM is full the hardware keeps looking for a 9th sprite, but it
+			//  increments the byte offset along with the sprite index. It ends up treating tile
+			//  numbers, attributes and X positions as Y, so the flag is set (or not) wrongly.
+			let mut m = 0;
+			while n < 64 {
+				if in_range(self.oam[n * 4 + m]) {
+					self.status |= STATUS_SPRITE_OVERFLOW;
+					break;
+				}
+				n += 1;
+				m = (m + 1) & 0x03;
+			}
+		}
+
+		for (slot, index) in found.iter().enumerate() {
+			let sprite = &self.oam[index * 4..index * 4 + 4];
+			let (y, tile, attributes, x) = (sprite[0], sprite[1], sprite[2], sprite[3]);
+
+			let mut row = self.scanline - y as u16;
+			if attributes & SPRITE_FLIP_VERTICAL != 0 {
+				row = height - 1 - row;
+			}
+
+			let pattern_addr = if height == 16 {
+				// 8x16 sprites pick their pattern table with bit 0 of the tile number
+				let table: u16 = if tile & 0x01 != 0 { 0x1000 } else { 0x0000 };
+				let tile = (tile & 0xFE) as u16 + if row >= 8 { 1 } else { 0 };
+				table + tile * 16 + (row & 0x07)
+			}
+			else {
+				let table: u16 = if self.ctrl & CTRL_SPRITE_TABLE != 0 { 0x1000 } else { 0x0000 };
+				table + (tile as u16) * 16 + row
+			};
+
+			let mut low = self.read(pattern_addr);
+			let mut high = self.read(pattern_addr + 8);
+			if attributes & SPRITE_FLIP_HORIZONTAL != 0 {
+				low = low.reverse_bits();
+				high = high.reverse_bits();
+			}
+
+			self.sprite_patterns_low[slot] = low;
+			self.sprite_patterns_high[slot] = high;
+			self.sprite_positions[slot] = x;
+			self.sprite_attributes[slot] = attributes;
+		}
+
+		// Empty slots still fetch tile $FF. Nothing gets drawn from it, but MMC3 counts 
+		//  scanlines by watching these fetches go by.
+		let empty_pattern_addr = if height == 16 {
+			0x1000 + 0xFE * 16
+		}
+		else {
+			(if self.ctrl & CTRL_SPRITE_TABLE != 0 { 0x1000 } else { 0x0000 }) + 0xFF * 16
+		};
+		for _ in found.len()..SPRITES_PER_SCANLINE {
+			self.read(empty_pattern_addr);
+			self.read(empty_pattern_addr + 8);
+		}
+
+		self.sprite_count = found.len();
+		self.sprite_zero_on_line = found.first() == Some(&0);
+	}
+
+	// Returns the pixel (0 - 3), palette (0 - 3), whether it goes behind the background and
+	//  whether it came from sprite 0, for the first opaque sprite at the current dot
+	fn sprite_pixel(&self) -> Option<(u8, u8, bool, bool)> {
+		if self.mask & MASK_SHOW_SPRITES == 0 {
+			return None;
+		}
+		if self.dot <= 8 && self.mask & MASK_SHOW_SPRITES_LEFT == 0 {
+			return None;
+		}
+
+		let x = self.dot - 1;
+		for slot in 0..self.sprite_count {
+			let offset = x.wrapping_sub(self.sprite_positions[slot] as u16);
+			if offset >= 8 {
+				continue;
+			}
+
+			let bit = 0x80 >> offset;
+			let pixel = ((self.sprite_patterns_low[slot] & bit != 0) as u8) | ((self.sprite_patterns_high[slot] & bit != 0) as u8) << 1;
+			if pixel == 0 {
+				continue;
+			}
+
+			let attributes = self.sprite_attributes[slot];
+			return Some((pixel, attributes & SPRITE_PALETTE, attributes & SPRITE_BEHIND_BACKGROUND != 0, slot == 0 && self.sprite_zero_on_line));
+		}
+
+		None
+	}
+
+	fn draw_pixel(&mut self) {
+		let (bg_pixel, bg_palette) = self.background_pixel();
+
+		// Pixel 0 of every palette is the shared backdrop colour
+		let mut palette_addr = if bg_pixel == 0 { PALETTE_START } else { PALETTE_START + ((bg_palette << 2) | bg_pixel) as u16 };
+
+		if let Some((pixel, palette, behind_background, is_sprite_zero)) = self.sprite_pixel() {
+			// Sprite 0 hit needs both to be opaque, and never happens on the last pixel
+			if is_sprite_zero && bg_pixel != 0 && self.dot != FRAME_WIDTH as u16 {
+				self.status |= STATUS_SPRITE_ZERO_HIT;
+			}
+
+			if bg_pixel == 0 || !behind_background {
+				palette_addr = SPRITE_PALETTE_START + ((palette << 2) | pixel) as u16;
+			}
+		}
+
+		let mut colour = self.read(palette_addr) & 0x3F;
+		if self.mask & MASK_GREYSCALE != 0 {
+			colour &= 0x30;
+		}
+
+		let x = (self.dot - 1) as usize;
+		let y = self.scanline as usize;
+		self.frame[y * FRAME_WIDTH + x] = colour;
+	}
+
+	// v is laid out as yyy NN YYYYY XXXXX
+	//  fine Y, nametable select, coarse Y, coarse X
+	fn increment_scroll_x(&mut self) {
+		if self.v & 0x001F == 31 {
+			self.v &= !0x001F;
+			self.v ^= 0x0400;
+		}
+		else {
+			self.v += 1;
+		}
+	}
+
+	fn increment_scroll_y(&mut self) {
+		if self.v & 0x7000 != 0x7000 {
+			self.v += 0x1000;
+			return;
+		}
+
+		self.v &= !0x7000;
+		let mut coarse_y = (self.v & 0x03E0) >> 5;
+		if coarse_y == 29 {
+			// Row 29 is the last row of tiles, the next nametable down comes after it
+			coarse_y = 0;
+			self.v ^= 0x0800;
+		}
+		else if coarse_y == 31 {
+			// 30 and 31 are in the attribute table, going past them wraps without switching
+			coarse_y = 0;
+		}
+		else {
+			coarse_y += 1;
+		}
+		self.v = (self.v & !0x03E0) | (coarse_y << 5);
+	}
+
+	fn transfer_address_x(&mut self) {
+		self.v = (self.v & !0x041F) | (self.t & 0x041F);
+	}
+
+	fn transfer_address_y(&mut self) {
+		self.v = (self.v & !0x7BE0) | (self.t & 0x7BE0);
+	}
+
+	// addr is the CPU address, so anything from $2000 - $3FFF
+	pub fn read_register(&mut self, addr: u16) -> u8 {
+		let data = match PPUCTRL + (addr & 0x0007) {
+			PPUSTATUS => {
+				let data = (self.status & 0xE0) | (self.data_bus & 0x1F);
+				self.status &= !STATUS_VBLANK;
+				self.w = false;
+				data
+			},
+			OAMDATA => self.read_oam(),
+			PPUDATA => {
+				let addr = self.v & 0x3FFF;
+				let data = if addr >= PALETTE_START {
+					// Palette reads skip the buffer, but the buffer still gets filled with
+					//  the nametable byte "underneath" the palette
+					self.read_buffer = self.read(addr - 0x1000);
+					self.read(addr)
+				}
+				else {
+					let data = self.read_buffer;
+					self.read_buffer = self.read(addr);
+					data
+				};
+				self.increment_vram_address();
+				data
+			},
+			_ => self.data_bus
+		};
+
+		self.data_bus = data;
+		data
+	}
+
+	// Same as read_register, minus clearing vblank, the toggle and the buffer
+	pub fn peek_register(&self, addr: u16) -> u8 {
+		match PPUCTRL + (addr & 0x0007) {
+			PPUSTATUS => (self.status & 0xE0) | (self.data_bus & 0x1F),
+			OAMDATA => self.read_oam(),
+			PPUDATA => {
+				let addr = self.v & 0x3FFF;
+				if addr >= PALETTE_START { self.read(addr) } else { self.read_buffer }
+			},
+			_ => self.data_bus
+		}
+	}
+
+	pub fn write_register(&mut self, addr: u16, data: u8) {
+		self.data_bus = data;
+
+		match PPUCTRL + (addr & 0x0007) {
+			PPUCTRL => {
+				// Turning NMIs on in the middle of vblank fires one straight away
+				if self.ctrl & CTRL_NMI_ENABLE == 0 && data & CTRL_NMI_ENABLE != 0 && self.status & STATUS_VBLANK != 0 {
+					self.nmi_pending = true;
+				}
+				self.ctrl = data;
+				self.t = (self.t & !0x0C00) | (((data & CTRL_NAMETABLE) as u16) << 10);
+			},
+			PPUMASK => self.mask = data,
+			PPUSTATUS => { },
+			OAMADDR => self.oam_addr = data,
+			OAMDATA => {
+				self.oam[self.oam_addr as usize] = data;
+				self.oam_addr = self.oam_addr.wrapping_add(1);
+			},
+			PPUSCROLL => {
+				if !self.w {
+					self.t = (self.t & !0x001F) | (data >> 3) as u16;
+					self.x = data & 0x07;
+				}
+				else {
+					self.t = (self.t & !0x73E0) | (((data & 0x07) as u16) << 12) | (((data & 0xF8) as u16) << 2);
+				}
+				self.w = !self.w;
+			},
+			PPUADDR => {
+				if !self.w {
+					// Only 6 bits make it in, bit 14 gets cleared
+					self.t = (self.t & 0x00FF) | (((data & 0x3F) as u16) << 8);
+				}
+				else {
+					self.t = (self.t & 0xFF00) | data as u16;
+					self.v = self.t;
+				}
+				self.w = !self.w;
+			},
+			PPUDATA => {
+				self.write(self.v & 0x3FFF, data);
+				self.increment_vram_address();
+			},
+			_ => { }
+		}
+	}
+
+	// Reads from the PPU's own address space
+	pub fn read(&self, addr: u16) -> u8 {
+		let addr = addr & 0x3FFF;
+		self.put_address_on_bus(addr);
+
+		match addr {
+			0x0000 ..= 0x1FFF => match &self.cartridge {
+				Some(cartridge) => cartridge.borrow().ppu_read(addr),
+				None => self.chr[addr as usize]
+			},
+			NAMETABLE_START ..= NAMETABLE_MIRROR_END => {
+				let index = self.mirror_nametable_addr(addr);
+				if index < VRAM_SIZE { self.vram[index] } else { self.four_screen_vram[index - VRAM_SIZE] }
+			},
+			_ => self.palette[mirror_palette_addr(addr)]
+		}
+	}
+
+	pub fn write(&mut self, addr: u16, data: u8) {
+		let addr = addr & 0x3FFF;
+		self.put_address_on_bus(addr);
+
+		match addr {
+			0x0000 ..= 0x1FFF => match &self.cartridge {
+				Some(cartridge) => cartridge.borrow_mut().ppu_write(addr, data),
+				None => self.chr[addr as usize] = data
+			},
+			NAMETABLE_START ..= NAMETABLE_MIRROR_END => {
+				let index = self.mirror_nametable_addr(addr);
+				if index < VRAM_SIZE { self.vram[index] = data; } else { self.four_screen_vram[index - VRAM_SIZE] = data; }
+			},
+			_ => self.palette[mirror_palette_addr(addr)] = data
+		}
+	}
+
+	// The palette is inside the PPU, everything else goes out to the cartridge where the
+	//  mapper can see it
+	fn put_address_on_bus(&self, addr: u16) {
+		if addr >= PALETTE_START {
+			return;
+		}
+
+		if let Some(cartridge) = &self.cartridge {
+			cartridge.borrow_mut().ppu_address(addr, self.cycles);
+		}
+	}
+
+	fn read_oam(&self) -> u8 {
+		let data = self.oam[self.oam_addr as usize];
+
+		// Bits 2-4 of the sprite attribute byte don't exist
+		if self.oam_addr & 0x03 == 2 { data & 0xE3 } else { data }
+	}
+
+	fn increment_vram_address(&mut self) {
+		let increment = if self.ctrl & CTRL_VRAM_INCREMENT != 0 { 32 } else { 1 };
+		self.v = self.v.wrapping_add(increment) & 0x7FFF;
+	}
+
+	pub fn nametable_mirroring(&self) -> Mirroring {
+		match &self.cartridge {
+			Some(cartridge) => cartridge.borrow().mirroring(),
+			None => self.mirroring
+		}
+	}
+
+	// Turns $2000 - $3EFF into an index into the 2KB of VRAM. There are four logical
+	//  nametables but only room for two, which two share memory is up to the cartridge.
+	//  Four screen goes past the end into four_screen_vram.
+	fn mirror_nametable_addr(&self, addr: u16) -> usize {
+		let addr = (addr - NAMETABLE_START) as usize % (NAMETABLE_SIZE * 4);
+		let table = addr / NAMETABLE_SIZE;
+		let offset = addr % NAMETABLE_SIZE;
+
+		let physical_table = match self.nametable_mirroring() {
+			Mirroring::Horizontal => table / 2,
+			Mirroring::Vertical => table % 2,
+			Mirroring::SingleScreenLower => 0,
+			Mirroring::SingleScreenUpper => 1,
+			Mirroring::FourScreen => table
+		};
+
+		physical_table * NAMETABLE_SIZE + offset
+	}
+}
+
+// $3F10, $3F14, $3F18 and $3F1C are the same bytes as $3F00, $3F04, $3F08 and $3F0C
+fn mirror_palette_addr(addr: u16) -> usize {
+	let index = (addr as usize) % PALETTE_SIZE;
+	if index >= 0x10 && index.is_multiple_of(4) { index - 0x10 } else { index }
+}
@@ -0,0 +1,506 @@
+use crate::audio::apu::*;
+use crate::audio::pulse::Pulse;
+use crate::audio::units::Envelope;
+use crate::audio::triangle::Triangle;
+use crate::audio::noise::Noise;
+use crate::bus::*;
+use crate::file_format::INES;
+use crate::tests::test_util::create_ines_image;
+
+// CPU cycles in one run of the 4 step frame sequence
+const FRAME_SEQUENCE_CYCLES: u32 = 29830;
+
+// Enabled, constant volume 15 at 50% duty, with a period of 8 and the length counter loaded with 10
+fn create_pulse(ones_complement: bool) -> Pulse {
+    let mut pulse = Pulse::new(ones_complement);
+    pulse.length_counter.set_enabled(true);
+    pulse.write_register(0, 0b1001_1111);
+    pulse.write_register(2, 0x08);
+    pulse.write_register(3, 0x00);
+    pulse
+}
+
+// Clocks the timer through one step of the duty sequence
+fn step_sequence(pulse: &mut Pulse) {
+    for _ in 0..=pulse.timer_period {
+        pulse.clock_timer();
+    }
+}
+
+fn tick(apu: &mut APU, cycles: u32) {
+    for _ in 0..cycles {
+        apu.tick();
+    }
+}
+
+#[test]
+fn pulse_duty_sequence() {
+    let mut pulse = create_pulse(true);
+
+    // 50% duty is 0 1 1 1 1 0 0 0
+    let mut outputs = vec![pulse.output()];
+    for _ in 0..7 {
+        step_sequence(&mut pulse);
+        outputs.push(pulse.output());
+    }
+
+    assert_eq!(outputs, vec![0, 15, 15, 15, 15, 0, 0, 0]);
+}
+
+#[test]
+fn pulse_timer_runs_at_half_cpu_speed() {
+    let mut apu = APU::new();
+    apu.write_register(APU_STATUS, STATUS_PULSE_1);
+    apu.write_register(0x4000, 0b1001_1111);
+    apu.write_register(0x4002, 0x08);
+    apu.write_register(0x4003, 0x00);
+
+    // The timer reloads at zero, so 9 APU cycles per step, 18 CPU cycles. The first 
+    //  step comes early since the timer starts at 0.
+    tick(&mut apu, 2);
+    assert_eq!(apu.pulse_1.output(), 15);
+    tick(&mut apu, 18 * 4);
+    assert_eq!(apu.pulse_1.output(), 0);
+}
+
+#[test]
+fn pulse_silent_with_low_period() {
+    let mut pulse = create_pulse(true);
+    pulse.write_register(2, 0x07);
+    step_sequence(&mut pulse);
+
+    assert_eq!(pulse.output(), 0);
+}
+
+#[test]
+fn length_counter_only_loads_when_enabled() {
+    let mut apu = APU::new();
+    apu.write_register(0x4003, 0x08);
+    assert_eq!(apu.read_register(APU_STATUS), 0x00);
+
+    apu.write_register(APU_STATUS, STATUS_PULSE_1 | STATUS_PULSE_2);
+    apu.write_register(0x4003, 0x08);
+    apu.write_register(0x4007, 0x08);
+    assert_eq!(apu.read_register(APU_STATUS), STATUS_PULSE_1 | STATUS_PULSE_2);
+
+    // Turning a channel off clears its length counter
+    apu.write_register(APU_STATUS, STATUS_PULSE_2);
+    assert_eq!(apu.read_register(APU_STATUS), STATUS_PULSE_2);
+}
+
+#[test]
+fn length_counter_counts_half_frames() {
+    let mut apu = APU::new();
+    apu.write_register(FRAME_COUNTER, FRAME_COUNTER_IRQ_INHIBIT);
+    apu.write_register(APU_STATUS, STATUS_PULSE_1);
+    // Index 0 is 10 half frames, there are two half frames in each sequence
+    apu.write_register(0x4003, 0x00);
+
+    tick(&mut apu, FRAME_SEQUENCE_CYCLES * 4);
+    assert_eq!(apu.read_register(APU_STATUS), STATUS_PULSE_1);
+
+    tick(&mut apu, FRAME_SEQUENCE_CYCLES);
+    assert_eq!(apu.read_register(APU_STATUS), 0x00);
+}
+
+#[test]
+fn length_counter_halt() {
+    let mut apu = APU::new();
+    apu.write_register(FRAME_COUNTER, FRAME_COUNTER_IRQ_INHIBIT);
+    apu.write_register(APU_STATUS, STATUS_PULSE_1);
+    apu.write_register(0x4000, 0x20);
+    apu.write_register(0x4003, 0x00);
+
+    tick(&mut apu, FRAME_SEQUENCE_CYCLES * 10);
+    assert_eq!(apu.read_register(APU_STATUS), STATUS_PULSE_1);
+}
+
+#[test]
+fn envelope_decays() {
+    let mut envelope = Envelope::new();
+    envelope.start = true;
+
+    envelope.clock();
+    assert_eq!(envelope.output(), 15);
+
+    // A period of 0 drops one step every clock, then stays at 0
+    for volume in (0..15).rev() {
+        envelope.clock();
+        assert_eq!(envelope.output(), volume);
+    }
+    envelope.clock();
+    assert_eq!(envelope.output(), 0);
+}
+
+#[test]
+fn envelope_divider() {
+    let mut envelope = Envelope::new();
+    envelope.start = true;
+    envelope.volume = 2;
+    envelope.clock();
+
+    // Every third clock with a period of 2
+    envelope.clock();
+    envelope.clock();
+    assert_eq!(envelope.output(), 15);
+    envelope.clock();
+    assert_eq!(envelope.output(), 14);
+}
+
+#[test]
+fn envelope_loops() {
+    let mut envelope = Envelope::new();
+    envelope.start = true;
+    envelope.looping = true;
+
+    for _ in 0..16 {
+        envelope.clock();
+    }
+    assert_eq!(envelope.output(), 0);
+
+    envelope.clock();
+    assert_eq!(envelope.output(), 15);
+}
+
+#[test]
+fn envelope_constant_volume() {
+    let mut envelope = Envelope::new();
+    envelope.start = true;
+    envelope.constant_volume = true;
+    envelope.volume = 7;
+
+    envelope.clock();
+    envelope.clock();
+    assert_eq!(envelope.output(), 7);
+}
+
+#[test]
+fn envelope_restarts_on_length_write() {
+    let mut pulse = create_pulse(true);
+    pulse.write_register(0, 0b1000_0000);
+    pulse.write_register(3, 0x00);
+    pulse.clock_quarter_frame();
+    pulse.clock_quarter_frame();
+    step_sequence(&mut pulse);
+    assert_eq!(pulse.output(), 14);
+
+    pulse.write_register(3, 0x00);
+    pulse.clock_quarter_frame();
+    step_sequence(&mut pulse);
+    assert_eq!(pulse.output(), 15);
+}
+
+#[test]
+fn sweep_up() {
+    let mut pulse = create_pulse(true);
+    pulse.write_register(2, 0x00);
+    pulse.write_register(3, 0x01);
+    // Enabled, divider period 0, shift 1
+    pulse.write_register(1, 0b1000_0001);
+
+    pulse.clock_half_frame();
+    assert_eq!(pulse.timer_period, 0x180);
+}
+
+#[test]
+fn sweep_negate_differs_between_channels() {
+    let mut pulse_1 = create_pulse(true);
+    let mut pulse_2 = create_pulse(false);
+    for pulse in [&mut pulse_1, &mut pulse_2].iter_mut() {
+        pulse.write_register(2, 0x00);
+        pulse.write_register(3, 0x01);
+        pulse.write_register(1, 0b1000_1001);
+        pulse.clock_half_frame();
+    }
+
+    // Pulse 1 subtracts one more than pulse 2
+    assert_eq!(pulse_1.timer_period, 0x7F);
+    assert_eq!(pulse_2.timer_period, 0x80);
+}
+
+#[test]
+fn sweep_divider_period() {
+    let mut pulse = create_pulse(true);
+    pulse.write_register(2, 0x00);
+    pulse.write_register(3, 0x01);
+    // Divider period 2, so the first half frame reloads the divider and then every third one updates
+    pulse.write_register(1, 0b1010_0001);
+
+    pulse.clock_half_frame();
+    assert_eq!(pulse.timer_period, 0x180);
+    pulse.clock_half_frame();
+    pulse.clock_half_frame();
+    assert_eq!(pulse.timer_period, 0x180);
+    pulse.clock_half_frame();
+    assert_eq!(pulse.timer_period, 0x240);
+}
+
+#[test]
+fn sweep_target_overflow_mutes_even_when_disabled() {
+    let mut pulse = create_pulse(true);
+    pulse.write_register(2, 0x00);
+    pulse.write_register(3, 0x04);
+    // Disabled, shift 0, so the target is double the period
+    pulse.write_register(1, 0b0000_0000);
+    step_sequence(&mut pulse);
+
+    assert_eq!(pulse.output(), 0);
+    pulse.clock_half_frame();
+    assert_eq!(pulse.timer_period, 0x400);
+}
+
+#[test]
+fn apu_registers_on_bus() {
+    let mut bus = NESBus::new();
+    bus.write(0x4015, 0x03);
+    bus.write(0x4003, 0x08);
+    bus.write(0x4007, 0x08);
+
+    assert_eq!(bus.read(0x4015), 0x03);
+    assert_eq!(bus.peek(0x4015), 0x03);
+}
+
+// Length and linear counters loaded, period of 2
+fn create_triangle() -> Triangle {
+    let mut triangle = Triangle::new();
+    triangle.length_counter.set_enabled(true);
+    triangle.write_register(0, 0x05);
+    triangle.write_register(2, 0x02);
+    triangle.write_register(3, 0x00);
+    triangle.clock_quarter_frame();
+    triangle
+}
+
+fn step_triangle(triangle: &mut Triangle) {
+    for _ in 0..=triangle.timer_period {
+        triangle.clock_timer();
+    }
+}
+
+#[test]
+fn triangle_sequence() {
+    let mut triangle = create_triangle();
+
+    let mut outputs = vec![triangle.output()];
+    for _ in 0..32 {
+        step_triangle(&mut triangle);
+        outputs.push(triangle.output());
+    }
+
+    assert_eq!(outputs[..4], [15, 14, 13, 12]);
+    assert_eq!(outputs[15..19], [0, 0, 1, 2]);
+    assert_eq!(outputs[31..], [15, 15]);
+}
+
+#[test]
+fn triangle_linear_counter_stops_sequencer() {
+    let mut triangle = create_triangle();
+
+    // Loaded with 5, the reload flag was cleared by the first clock since control is off
+    for _ in 0..5 {
+        triangle.clock_quarter_frame();
+    }
+    step_triangle(&mut triangle);
+    step_triangle(&mut triangle);
+
+    // Holds wherever it was rather than dropping to 0
+    assert_eq!(triangle.output(), 15);
+}
+
+#[test]
+fn triangle_control_keeps_reloading() {
+    let mut triangle = create_triangle();
+    triangle.write_register(0, 0x81);
+    triangle.write_register(3, 0x00);
+
+    for _ in 0..5 {
+        triangle.clock_quarter_frame();
+    }
+    step_triangle(&mut triangle);
+
+    assert_eq!(triangle.output(), 14);
+}
+
+#[test]
+fn triangle_ultrasonic_period_holds() {
+    let mut triangle = create_triangle();
+    triangle.write_register(2, 0x01);
+    step_triangle(&mut triangle);
+    step_triangle(&mut triangle);
+
+    assert_eq!(triangle.output(), 15);
+}
+
+// Constant volume 15, shortest period
+fn create_noise(short_mode: bool) -> Noise {
+    let mut noise = Noise::new();
+    noise.length_counter.set_enabled(true);
+    noise.write_register(0, 0x1F);
+    noise.write_register(2, if short_mode { 0x80 } else { 0x00 });
+    noise.write_register(3, 0x00);
+    noise
+}
+
+// The output after each of the next count shifts
+fn noise_outputs(noise: &mut Noise, count: usize) -> Vec<u8> {
+    let mut outputs = Vec::new();
+    for _ in 0..count {
+        for _ in 0..4 {
+            noise.clock_timer();
+        }
+        outputs.push(noise.output());
+    }
+    outputs
+}
+
+#[test]
+fn noise_short_mode_repeats_every_93() {
+    let mut noise = create_noise(true);
+    let outputs = noise_outputs(&mut noise, 93 * 2);
+
+    assert_eq!(outputs[..93], outputs[93..]);
+    assert!(outputs.contains(&15));
+    assert!(outputs.contains(&0));
+}
+
+#[test]
+fn noise_long_mode_does_not_repeat_every_93() {
+    let mut noise = create_noise(false);
+    let outputs = noise_outputs(&mut noise, 93 * 2);
+
+    assert_ne!(outputs[..93], outputs[93..]);
+}
+
+#[test]
+fn noise_silenced_by_length_counter() {
+    let mut noise = create_noise(false);
+    noise.length_counter.set_enabled(false);
+
+    assert!(noise_outputs(&mut noise, 50).iter().all(|output| *output == 0));
+}
+
+// A bus with a cartridge whose $C000 - $FFFF is all $FF and a 1 byte sample at the fastest rate
+fn create_bus_with_dmc_sample(flags: u8) -> NESBus {
+    let mut image = create_ines_image(2, 1, 0x00, 0x00);
+    for byte in image[16 + 0x4000..16 + 0x8000].iter_mut() {
+        *byte = 0xFF;
+    }
+
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(image).unwrap()).unwrap();
+    bus.write(0x4010, flags | 0x0F);
+    bus.write(0x4011, 64);
+    bus.write(0x4012, 0x00);
+    bus.write(0x4013, 0x00);
+    bus.write(0x4015, STATUS_DMC);
+    bus
+}
+
+#[test]
+fn dmc_direct_load() {
+    let mut apu = APU::new();
+    apu.write_register(0x4011, 0xFF);
+
+    assert_eq!(apu.dmc.output(), 0x7F);
+}
+
+#[test]
+fn dmc_fetch_stalls_cpu() {
+    let mut bus = create_bus_with_dmc_sample(0x00);
+    assert_eq!(bus.read(0x4015) & STATUS_DMC, STATUS_DMC);
+
+    bus.tick(1);
+
+    assert_eq!(bus.take_stall_cycles(false), DMC_DMA_CYCLES);
+    assert_eq!(bus.take_stall_cycles(false), 0);
+    assert_eq!(bus.read(0x4015) & STATUS_DMC, 0);
+}
+
+#[test]
+fn dmc_plays_sample() {
+    let mut bus = create_bus_with_dmc_sample(0x00);
+
+    // 8 bits of silence while the first byte is waiting, then 8 ones each adding 2
+    bus.tick(54 * 20);
+
+    assert_eq!(bus.apu.dmc.output(), 64 + 16);
+}
+
+#[test]
+fn dmc_irq_at_end_of_sample() {
+    let mut bus = create_bus_with_dmc_sample(0x80);
+    assert!(!bus.irq());
+
+    bus.tick(1);
+    assert!(bus.irq());
+    assert_eq!(bus.peek(0x4015) & STATUS_DMC_IRQ, STATUS_DMC_IRQ);
+
+    // Writing $4015 acknowledges it
+    bus.write(0x4015, 0x00);
+    assert!(!bus.irq());
+}
+
+#[test]
+fn dmc_loops() {
+    let mut bus = create_bus_with_dmc_sample(0x40);
+    bus.tick(54 * 20);
+
+    assert_eq!(bus.read(0x4015) & STATUS_DMC, STATUS_DMC);
+    assert!(!bus.irq());
+}
+
+#[test]
+fn frame_irq_in_four_step_mode() {
+    let mut apu = APU::new();
+
+    tick(&mut apu, 29828);
+    assert!(!apu.irq());
+    tick(&mut apu, 1);
+    assert!(apu.irq());
+
+    // Reading $4015 acknowledges it
+    assert_eq!(apu.read_register(APU_STATUS) & STATUS_FRAME_IRQ, STATUS_FRAME_IRQ);
+    assert!(!apu.irq());
+    assert_eq!(apu.read_register(APU_STATUS) & STATUS_FRAME_IRQ, 0);
+}
+
+#[test]
+fn frame_irq_inhibit() {
+    let mut apu = APU::new();
+    tick(&mut apu, FRAME_SEQUENCE_CYCLES);
+    assert!(apu.irq());
+
+    // Setting the inhibit flag also clears it
+    apu.write_register(FRAME_COUNTER, FRAME_COUNTER_IRQ_INHIBIT);
+    assert!(!apu.irq());
+
+    tick(&mut apu, FRAME_SEQUENCE_CYCLES * 2);
+    assert!(!apu.irq());
+}
+
+#[test]
+fn five_step_mode() {
+    let mut apu = APU::new();
+    apu.write_register(APU_STATUS, STATUS_PULSE_1);
+    apu.write_register(0x4003, 0x00);
+
+    // Switching to 5 step mode clocks a half frame straight away, the length counter 
+    //  was 10 so 9 more half frames to go, two per 37282 cycle sequence and no IRQ
+    apu.write_register(FRAME_COUNTER, FRAME_COUNTER_FIVE_STEP);
+    tick(&mut apu, 37282 * 4);
+    assert_eq!(apu.read_register(APU_STATUS), STATUS_PULSE_1);
+    assert!(!apu.irq());
+
+    tick(&mut apu, 37282);
+    assert_eq!(apu.read_register(APU_STATUS), 0x00);
+}
+
+#[test]
+fn apu_irq_reaches_bus() {
+    let mut bus = NESBus::new();
+    for _ in 0..FRAME_SEQUENCE_CYCLES {
+        bus.tick(1);
+    }
+
+    assert!(bus.irq());
+}
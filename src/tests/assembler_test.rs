@@ -0,0 +1,133 @@
+use crate::assembler::*;
+use crate::cpu::*;
+
+#[test]
+fn write_first_0xff() {
+    let source = include_str!("../../integration_tests/write_first_0xff.asm6502");
+
+    // What the old external assembler produced for the same file
+    let expected: Vec<u8> = vec![0xA0, 0x01, 0xA9, 0x00, 0xC8, 0xE6, 0x00, 0x98, 0xAA, 0x94, 0x00, 0xC0, 0xFF, 0xD0, 0xF5];
+    assert_eq!(assemble(source, PROGRAM_START_ADDR).unwrap(), expected);
+}
+
+#[test]
+fn write_load_and_loop() {
+    let source = include_str!("../../integration_tests/write_load_and_loop.asm6502");
+
+    let expected: Vec<u8> = vec![0xA6, 0x40, 0xA5, 0x40, 0x69, 0x10, 0x85, 0x41, 0xE8, 0xE4, 0x41, 0xD0, 0xFB];
+    assert_eq!(assemble(source, PROGRAM_START_ADDR).unwrap(), expected);
+}
+
+#[test]
+fn primes_assembles() {
+    let source = include_str!("../../integration_tests/primes.asm6502");
+
+    assert!(assemble(source, PROGRAM_START_ADDR).is_ok());
+}
+
+#[test]
+fn last_line_is_not_dropped() {
+    let program = assemble("LDA #$01\nTAX", PROGRAM_START_ADDR).unwrap();
+
+    assert_eq!(program, vec![0xA9, 0x01, 0xAA]);
+}
+
+#[test]
+fn comments_inside_loops() {
+    let source = "loop: ; top of the loop\n  INX ; count\n  BNE loop ; again\n";
+
+    assert_eq!(assemble(source, PROGRAM_START_ADDR).unwrap(), vec![0xE8, 0xD0, 0xFD]);
+}
+
+#[test]
+fn forward_labels_are_absolute() {
+    let source = "JSR sub\nJMP end\nsub:\n  RTS\nend:\n  NOP";
+
+    assert_eq!(assemble(source, 0x8000).unwrap(), vec![0x20, 0x06, 0x80, 0x4C, 0x07, 0x80, 0x60, 0xEA]);
+}
+
+#[test]
+fn defines() {
+    let source = "define counter $10\ndefine limit 200\nLDA counter\nCMP #limit\nSTA counter,X";
+
+    assert_eq!(assemble(source, PROGRAM_START_ADDR).unwrap(), vec![0xA5, 0x10, 0xC9, 200, 0x95, 0x10]);
+}
+
+#[test]
+fn addressing_modes() {
+    let source = "
+        LDA $1234,X
+        LDA $1234,Y
+        LDA ($20,X)
+        LDA ($20),y
+        LDX $10,Y
+        LDA $10,Y
+        JMP ($FFFC)
+        ASL
+        ROL A
+        LDA #%1010
+    ";
+    let expected: Vec<u8> = vec![
+        0xBD, 0x34, 0x12,
+        0xB9, 0x34, 0x12,
+        0xA1, 0x20,
+        0xB1, 0x20,
+        0xB6, 0x10,
+        // There is no zero page,Y LDA so it gets promoted
+        0xB9, 0x10, 0x00,
+        0x6C, 0xFC, 0xFF,
+        0x0A,
+        0x2A,
+        0xA9, 0x0A
+    ];
+
+    assert_eq!(assemble(source, PROGRAM_START_ADDR).unwrap(), expected);
+}
+
+#[test]
+fn label_low_and_high_bytes() {
+    let source = "LDA #<data\nLDX #>data\ndata:\ndcb 1, $02, %11";
+
+    assert_eq!(assemble(source, 0x8000).unwrap(), vec![0xA9, 0x04, 0xA2, 0x80, 0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn unofficial_mnemonics() {
+    let program = assemble("LAX $10\nNOP\nNOP $10", PROGRAM_START_ADDR).unwrap();
+
+    assert_eq!(program, vec![0xA7, 0x10, 0xEA, 0x04, 0x10]);
+}
+
+#[test]
+fn errors_report_line_numbers() {
+    assert_eq!(assemble("NOP\nFOO #$01", PROGRAM_START_ADDR).unwrap_err(), "Line 2: Unknown instruction [FOO]");
+    assert_eq!(assemble("BNE nowhere", PROGRAM_START_ADDR).unwrap_err(), "Line 1: Unknown label [nowhere]");
+    assert!(assemble("LDA #$1234", PROGRAM_START_ADDR).is_err());
+}
+
+#[test]
+fn dcb_values_must_fit_in_a_byte() {
+    assert_eq!(assemble("dcb $FF, $100", PROGRAM_START_ADDR).unwrap_err(), "Line 1: Value $100 does not fit in a byte");
+}
+
+#[test]
+fn branch_out_of_range() {
+    let mut source = String::from("start:\n");
+    for _ in 0..200 {
+        source.push_str("NOP\n");
+    }
+    source.push_str("BNE start\n");
+
+    assert!(assemble(&source, PROGRAM_START_ADDR).is_err());
+}
+
+#[test]
+fn assembled_program_runs() {
+    let source = include_str!("../../integration_tests/write_first_0xff.asm6502");
+    let mut cpu = CPU::new(assemble(source, PROGRAM_START_ADDR).unwrap());
+
+    cpu.run_program();
+
+    assert_eq!(cpu.registers.y, 0xFF);
+    assert_eq!(cpu.load(0x0005), 0x05);
+}
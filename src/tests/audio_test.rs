@@ -0,0 +1,146 @@
+use crate::audio::apu::*;
+use crate::audio::mixer::mix;
+use crate::audio::resampler::*;
+use crate::audio::wav::WavWriter;
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.0001, "{} is not close to {}", actual, expected);
+}
+
+#[test]
+fn mix_silence() {
+    assert_eq!(mix(0, 0, 0, 0, 0), 0.0);
+}
+
+#[test]
+fn mix_is_not_linear() {
+    // Both pulses at full volume come out less than twice one of them
+    let one = mix(15, 0, 0, 0, 0);
+    let both = mix(15, 15, 0, 0, 0);
+
+    assert_close(one, 0.1494);
+    assert_close(both, 0.2585);
+}
+
+#[test]
+fn mix_everything_at_full_volume() {
+    assert_close(mix(15, 15, 15, 15, 127), 1.0);
+}
+
+#[test]
+fn resampler_rate() {
+    for sample_rate in [44100, 48000].iter() {
+        let mut resampler = Resampler::new(*sample_rate);
+        for _ in 0..CPU_CLOCK_RATE as u32 {
+            resampler.push(0.5);
+        }
+
+        let samples = resampler.take_samples().len() as i64;
+        assert!((samples - *sample_rate as i64).abs() <= 1, "Got {} samples at {}Hz", samples, sample_rate);
+    }
+}
+
+#[test]
+fn resampler_removes_dc_offset() {
+    let mut resampler = Resampler::new(DEFAULT_SAMPLE_RATE);
+    for _ in 0..CPU_CLOCK_RATE as u32 {
+        resampler.push(0.5);
+    }
+
+    // The low passes take a few samples to catch up with the step
+    let samples = resampler.take_samples();
+    assert!(samples[..10].iter().any(|sample| *sample > 0.1));
+    assert!(samples.last().unwrap().abs() < 0.001);
+}
+
+#[test]
+fn resampler_filters_out_ultrasonic() {
+    // Flipping every cycle is way above anything that can be heard, and should average out
+    let mut resampler = Resampler::new(DEFAULT_SAMPLE_RATE);
+    for cycle in 0..100_000 {
+        resampler.push(if cycle % 2 == 0 { 1.0 } else { -1.0 });
+    }
+
+    assert!(resampler.take_samples().iter().all(|sample| sample.abs() < 0.05));
+}
+
+#[test]
+fn resampler_does_not_alias() {
+    // 30kHz can't be represented at 44.1kHz, without band limiting it would fold back 
+    //  down to 14.1kHz
+    let mut resampler = Resampler::new(DEFAULT_SAMPLE_RATE);
+    for cycle in 0..100_000 {
+        let time = cycle as f64 / CPU_CLOCK_RATE;
+        resampler.push((2.0 * std::f64::consts::PI * 30_000.0 * time).sin() as f32);
+    }
+
+    let samples = resampler.take_samples();
+    assert!(samples[100..].iter().all(|sample| sample.abs() < 0.01));
+}
+
+#[test]
+fn resampler_keeps_audible_tones() {
+    let mut resampler = Resampler::new(DEFAULT_SAMPLE_RATE);
+    for cycle in 0..100_000 {
+        let time = cycle as f64 / CPU_CLOCK_RATE;
+        resampler.push((2.0 * std::f64::consts::PI * 2_000.0 * time).sin() as f32);
+    }
+
+    let samples = resampler.take_samples();
+    assert!(samples[100..].iter().any(|sample| sample.abs() > 0.8));
+}
+
+#[test]
+fn take_samples_empties_buffer() {
+    let mut resampler = Resampler::new(DEFAULT_SAMPLE_RATE);
+    for _ in 0..1000 {
+        resampler.push(0.0);
+    }
+
+    assert!(!resampler.take_samples().is_empty());
+    assert!(resampler.take_samples().is_empty());
+}
+
+#[test]
+fn apu_produces_samples() {
+    let mut apu = APU::new();
+    apu.write_register(APU_STATUS, STATUS_PULSE_1);
+    apu.write_register(0x4000, 0b1011_1111);
+    apu.write_register(0x4002, 0xFD);
+    apu.write_register(0x4003, 0x00);
+
+    for _ in 0..29830 {
+        apu.tick();
+    }
+
+    let samples = apu.take_samples();
+    assert_eq!(samples.len(), 735);
+    assert!(samples.iter().any(|sample| sample.abs() > 0.05));
+}
+
+#[test]
+fn wav_file() {
+    let path = std::env::temp_dir().join("nes_emulator_audio_test.wav");
+
+    let mut writer = WavWriter::create(&path, 48000).unwrap();
+    writer.write_samples(&[0.0, 1.0, -1.0]).unwrap();
+    writer.write_samples(&[2.0]).unwrap();
+    writer.finish().unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+    let i16_at = |offset: usize| i16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+
+    assert_eq!(bytes.len(), 44 + 8);
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(u32_at(4), 36 + 8);
+    assert_eq!(&bytes[8..16], b"WAVEfmt ");
+    assert_eq!(u32_at(24), 48000);
+    assert_eq!(&bytes[36..40], b"data");
+    assert_eq!(u32_at(40), 8);
+
+    // Past 1.0 gets clipped
+    assert_eq!([i16_at(44), i16_at(46), i16_at(48), i16_at(50)], [0, i16::MAX, -i16::MAX, i16::MAX]);
+}
@@ -0,0 +1,146 @@
+use crate::bus::*;
+use crate::cpu::*;
+
+#[test]
+fn flat_bus_has_full_address_space() {
+    let mut bus = FlatBus::new();
+    bus.write(0xFFFF, 0x42);
+
+    assert_eq!(bus.read(0xFFFF), 0x42);
+}
+
+#[test]
+fn ram_is_mirrored() {
+    let mut bus = NESBus::new();
+    bus.write(0x0012, 0x42);
+
+    assert_eq!(bus.read(0x0812), 0x42);
+    assert_eq!(bus.read(0x1012), 0x42);
+    assert_eq!(bus.read(0x1812), 0x42);
+
+    bus.write(0x1FFF, 0x24);
+    assert_eq!(bus.read(0x07FF), 0x24);
+}
+
+#[test]
+fn ppu_registers_are_mirrored() {
+    let mut bus = NESBus::new();
+    // OAMADDR through a mirror, then OAMDATA through another
+    bus.write(0x200B, 0x05);
+    bus.write(0x3FFC, 0x42);
+
+    assert_eq!(bus.ppu.oam[0x05], 0x42);
+
+    bus.write(0x2003, 0x05);
+    assert_eq!(bus.read(0x2004), 0x42);
+}
+
+#[test]
+fn apu_io_registers_are_not_mirrored() {
+    let mut bus = NESBus::new();
+    bus.write(0x4018, 0x42);
+
+    assert_eq!(bus.read(0x4018), 0x42);
+    assert_eq!(bus.read(0x4020), 0x00);
+}
+
+#[test]
+fn cartridge_space() {
+    let mut bus = NESBus::new();
+    bus.load_program(0x8000, &[0xA9, 0x42]);
+
+    assert_eq!(bus.read(0x8000), 0xA9);
+    assert_eq!(bus.read(0x8001), 0x42);
+    assert_eq!(bus.read(0x0000), 0x00);
+}
+
+#[test]
+fn cpu_runs_on_nes_bus() {
+    let mut bus = NESBus::new();
+    // LDA #$42, STA $0800
+    bus.load_program(0x8000, &[0xA9, 0x42, 0x8D, 0x00, 0x08]);
+    bus.write(PROGRAM_READ_START, 0x00);
+    bus.write(PROGRAM_READ_START + 1, 0x80);
+
+    let mut cpu = CPU::with_bus(bus);
+    assert_eq!(cpu.program_counter, 0x8000);
+
+    cpu.run_next_instruction();
+    cpu.run_next_instruction();
+
+    // $0800 is a mirror of $0000
+    assert_eq!(cpu.load(0x0000), 0x42);
+}
+
+#[test]
+fn oam_dma_copies_a_page() {
+    let mut bus = NESBus::new();
+    for offset in 0..0x100 {
+        bus.write(0x0200 + offset, offset as u8);
+    }
+
+    bus.write(0x4014, 0x02);
+
+    for offset in 0..0x100 {
+        assert_eq!(bus.ppu.oam[offset], offset as u8);
+    }
+}
+
+#[test]
+fn oam_dma_starts_at_oam_addr() {
+    let mut bus = NESBus::new();
+    bus.write(0x0300, 0x42);
+    bus.write(0x03FF, 0x24);
+    bus.write(0x2003, 0x10);
+
+    bus.write(0x4014, 0x03);
+
+    assert_eq!(bus.ppu.oam[0x10], 0x42);
+    assert_eq!(bus.ppu.oam[0x0F], 0x24);
+    assert_eq!(bus.ppu.oam_addr, 0x10);
+}
+
+fn create_cpu_with_dma_program(program: &[u8]) -> CPU<NESBus> {
+    let mut bus = NESBus::new();
+    bus.load_program(0x8000, program);
+    bus.write(PROGRAM_READ_START, 0x00);
+    bus.write(PROGRAM_READ_START + 1, 0x80);
+    bus.write(0x0200, 0x99);
+
+    CPU::with_bus(bus)
+}
+
+#[test]
+fn oam_dma_stalls_cpu_on_even_cycle() {
+    // LDA $00, LDA #$02, STA $4014
+    let mut cpu = create_cpu_with_dma_program(&[0xA5, 0x00, 0xA9, 0x02, 0x8D, 0x14, 0x40]);
+
+    cpu.run_next_instruction();
+    cpu.run_next_instruction();
+    // Reset took 7, so the DMA starts on cycle 7 + 3 + 2 + 4 = 16
+    assert_eq!(cpu.run_next_instruction(), 4 + 513);
+    assert_eq!(cpu.cycles, 16 + 513);
+    assert_eq!(cpu.bus.ppu.oam[0], 0x99);
+}
+
+#[test]
+fn oam_dma_stalls_cpu_on_odd_cycle() {
+    // LDA #$02, STA $4014
+    let mut cpu = create_cpu_with_dma_program(&[0xA9, 0x02, 0x8D, 0x14, 0x40]);
+
+    cpu.run_next_instruction();
+    assert_eq!(cpu.run_next_instruction(), 4 + 514);
+    assert_eq!(cpu.cycles, 13 + 514);
+}
+
+#[test]
+fn ppu_keeps_running_during_oam_dma() {
+    let mut cpu = create_cpu_with_dma_program(&[0xA9, 0x02, 0x8D, 0x14, 0x40]);
+    let start = cpu.bus.ppu_position().unwrap();
+
+    cpu.run_next_instruction();
+    cpu.run_next_instruction();
+
+    let dots = |(scanline, dot): (u16, u16)| scanline as u64 * 341 + dot as u64;
+    assert_eq!(dots(cpu.bus.ppu_position().unwrap()) - dots(start), (2 + 4 + 514) * 3);
+}
@@ -0,0 +1,95 @@
+use crate::bus::*;
+use crate::controller::*;
+use crate::controller::buttons::*;
+use crate::cpu::CPU;
+
+fn read_all(controller: &mut Controller) -> Vec<u8> {
+    (0..8).map(|_| controller.read()).collect()
+}
+
+#[test]
+fn buttons_shift_out_in_order() {
+    let mut controller = Controller::new();
+    controller.set_buttons(BUTTON_A | BUTTON_START | BUTTON_RIGHT);
+    controller.write(1);
+    controller.write(0);
+
+    assert_eq!(read_all(&mut controller), vec![1, 0, 0, 1, 0, 0, 0, 1]);
+}
+
+#[test]
+fn reads_past_eight_are_ones() {
+    let mut controller = Controller::new();
+    controller.write(1);
+    controller.write(0);
+    read_all(&mut controller);
+
+    assert_eq!(read_all(&mut controller), vec![1; 8]);
+}
+
+#[test]
+fn strobe_high_keeps_returning_a() {
+    let mut controller = Controller::new();
+    controller.set_buttons(BUTTON_A);
+    controller.write(1);
+
+    assert_eq!(read_all(&mut controller), vec![1; 8]);
+
+    controller.set_buttons(BUTTON_B);
+    assert_eq!(controller.read(), 0);
+}
+
+#[test]
+fn buttons_latched_when_strobe_goes_low() {
+    let mut controller = Controller::new();
+    controller.set_buttons(BUTTON_B);
+    controller.write(1);
+    controller.write(0);
+
+    // Changes after the latch don't show up until the next strobe
+    controller.set_buttons(BUTTON_A);
+    assert_eq!(controller.read(), 0);
+    assert_eq!(controller.read(), 1);
+}
+
+#[test]
+fn peek_does_not_shift() {
+    let mut controller = Controller::new();
+    controller.set_buttons(BUTTON_A);
+    controller.write(1);
+    controller.write(0);
+
+    assert_eq!(controller.peek(), 1);
+    assert_eq!(controller.peek(), 1);
+    assert_eq!(controller.read(), 1);
+    assert_eq!(controller.peek(), 0);
+}
+
+#[test]
+fn both_controllers_strobed_by_4016() {
+    let mut bus = NESBus::new();
+    bus.controllers[0].set_buttons(BUTTON_A);
+    bus.controllers[1].set_buttons(BUTTON_B);
+    bus.write(JOYPAD_1, 1);
+    bus.write(JOYPAD_1, 0);
+
+    assert_eq!(bus.read(JOYPAD_1) & 0x01, 1);
+    assert_eq!(bus.read(JOYPAD_2) & 0x01, 0);
+    assert_eq!(bus.read(JOYPAD_2) & 0x01, 1);
+}
+
+#[test]
+fn upper_bits_are_open_bus() {
+    let mut bus = NESBus::new();
+    bus.controllers[0].set_buttons(BUTTON_A);
+    bus.write(JOYPAD_1, 1);
+    bus.write(JOYPAD_1, 0);
+    bus.load_program(0x8000, &[0xAD, 0x16, 0x40]);
+
+    // LDA $4016 leaves $40 on the bus from the address, so the read comes back as $41
+    let mut cpu = CPU::with_bus(bus);
+    cpu.program_counter = 0x8000;
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x41);
+}
@@ -0,0 +1,424 @@
+use crate::cpu::*;
+
+#[test]
+fn eor_immediate() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x49, test_value];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_zero_page() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x45, 0xFF];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x00FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_zero_page_x() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x55, 0xF0];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x00FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_absolute() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x4D, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_absolute_x() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x5D, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_absolute_y() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x59, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_indirect_x() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x41, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x04;
+    cpu.write16(0x0024, 0x3074);
+    cpu.write(0x3074, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn eor_indirect_y() {
+    let test_value: u8 = 0xFF;
+    let program: Vec<u8> = vec![0x51, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x04;
+    cpu.write16(0x0020, 0x3074);
+    cpu.write(0x3078, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xF0);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_immediate() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x09, test_value];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_zero_page() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x05, 0xFF];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x00FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_zero_page_x() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x15, 0xF0];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x00FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_absolute() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x0D, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_absolute_x() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x1D, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_absolute_y() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x19, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_indirect_x() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x01, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x04;
+    cpu.write16(0x0024, 0x3074);
+    cpu.write(0x3074, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn ora_indirect_y() {
+    let test_value: u8 = 0xF0;
+    let program: Vec<u8> = vec![0x11, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x04;
+    cpu.write16(0x0020, 0x3074);
+    cpu.write(0x3078, test_value);
+    cpu.registers.a = 0x0F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0xFF);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn sbc_immediate() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xE9, test_value];
+    let mut cpu = CPU::new(program);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_zero_page() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xE5, 0xFF];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x00FF, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_zero_page_x() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xF5, 0xF0];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x00FF, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_absolute() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xED, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x30FF, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_absolute_x() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xFD, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_absolute_y() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xF9, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x0F;
+    cpu.write(0x30FF, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_indirect_x() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xE1, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x04;
+    cpu.write16(0x0024, 0x3074);
+    cpu.write(0x3074, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_indirect_y() {
+    let test_value: u8 = 0x10;
+    let program: Vec<u8> = vec![0xF1, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x04;
+    cpu.write16(0x0020, 0x3074);
+    cpu.write(0x3078, test_value);
+    cpu.status = CARRY;
+    cpu.registers.a = 0x50;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sbc_overflow() {
+    let program: Vec<u8> = vec![0xE9, 0x01, 0xE9, 0xFF];
+    let mut cpu = CPU::new(program);
+
+    // -128 - 1 can't be represented
+    cpu.registers.a = 0x80;
+    cpu.status = CARRY;
+    cpu.run_next_instruction();
+    assert_eq!(cpu.registers.a, 0x7F);
+    assert_eq!(cpu.status, CARRY | OVERFLOW);
+
+    // 127 - (-1) can't either
+    cpu.status = CARRY;
+    cpu.run_next_instruction();
+    assert_eq!(cpu.registers.a, 0x80);
+    assert_eq!(cpu.status, OVERFLOW | NEGATIVE);
+}
+
+#[test]
+fn sbc_equal_values_sets_zero() {
+    let program: Vec<u8> = vec![0xE9, 0x42];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x42;
+    cpu.status = CARRY;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x00);
+    assert_eq!(cpu.status, CARRY | ZERO);
+}
+
+#[test]
+fn eor_clears_negative() {
+    let program: Vec<u8> = vec![0x49, 0x80];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x81;
+    cpu.status = NEGATIVE;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x01);
+    assert_eq!(cpu.status, 0);
+}
+
+#[test]
+fn indirect_y_pointer_wraps_in_zero_page() {
+    let program: Vec<u8> = vec![0x11, 0xFF];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x01;
+
+    // The high byte of the pointer comes from $00, not $100
+    cpu.write(0x00FF, 0x00);
+    cpu.write(0x0000, 0x30);
+    cpu.write(0x3001, 0x0F);
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x0F);
+}
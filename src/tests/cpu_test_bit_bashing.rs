@@ -34,7 +34,7 @@ fn asl_zero_page() {
 
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.memory[0xFF], test_value << 1);
+    assert_eq!(cpu.load(0xFF), test_value << 1);
     assert_eq!(cpu.status, CARRY | NEGATIVE);
 }
 
@@ -48,7 +48,7 @@ fn asl_zero_page_x() {
 
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.memory[0xFF], test_value << 1);
+    assert_eq!(cpu.load(0xFF), test_value << 1);
     assert_eq!(cpu.status, CARRY | NEGATIVE);
 }
 
@@ -61,7 +61,7 @@ fn asl_absolute() {
 
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.memory[0x3000], test_value << 1);
+    assert_eq!(cpu.load(0x3000), test_value << 1);
     assert_eq!(cpu.status, CARRY | NEGATIVE);
 }
 
@@ -75,7 +75,7 @@ fn asl_absolute_x() {
 
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.memory[0x3030], test_value << 1);
+    assert_eq!(cpu.load(0x3030), test_value << 1);
     assert_eq!(cpu.status, CARRY | NEGATIVE);
 }
 
@@ -100,7 +100,7 @@ fn rol_carry_set_after_rotation() {
     
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.status, CARRY);
+    assert_eq!(cpu.status, CARRY | ZERO);
 }
 
 #[test]
@@ -207,7 +207,7 @@ fn ror_carry_set_after_rotation() {
     
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.status, CARRY);
+    assert_eq!(cpu.status, CARRY | ZERO);
 }
 
 #[test]
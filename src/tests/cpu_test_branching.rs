@@ -18,7 +18,7 @@ fn bcc_carry_not_set() {
     let program: Vec<u8> = vec![0x90, jump_offset];
     let mut cpu = CPU::new(program);
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -31,7 +31,7 @@ fn bcs_carry_set() {
     let mut cpu = CPU::new(program);
     cpu.status = CARRY;
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -55,7 +55,7 @@ fn beq_zero_set() {
     let mut cpu = CPU::new(program);
     cpu.status = ZERO;
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -79,7 +79,7 @@ fn bmi_negative_set() {
     let mut cpu = CPU::new(program);
     cpu.status = NEGATIVE;
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -114,7 +114,7 @@ fn bne_zero_not_set() {
     let program: Vec<u8> = vec![0xD0, jump_offset];
     let mut cpu = CPU::new(program);
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -138,7 +138,7 @@ fn bpl_negative_not_set() {
     let program: Vec<u8> = vec![0x10, jump_offset];
     let mut cpu = CPU::new(program);
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -150,7 +150,7 @@ fn bvc_overflow_not_set() {
     let program: Vec<u8> = vec![0x50, jump_offset];
     let mut cpu = CPU::new(program);
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -188,7 +188,7 @@ fn bvs_overflow_set() {
     let mut cpu = CPU::new(program);
     cpu.status = OVERFLOW;
 
-    let expected_branch_location = cpu.program_counter as i16 + jump_offset as i16;
+    let expected_branch_location = cpu.program_counter as i16 + 2 + jump_offset as i16;
 
     cpu.run_next_instruction();
     assert_eq!(cpu.program_counter, expected_branch_location as u16);
@@ -209,18 +209,66 @@ fn jmp_absolute() {
 
 #[test]
 fn jmp_indirect() {
-    assert!(false);
+    let jump_addr: u16 = 0x4321;
+    let program: Vec<u8> = vec![0x6C, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write16(0x3000, jump_addr);
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, jump_addr);
+}
+
+#[test]
+fn jmp_indirect_page_boundary_bug() {
+    let program: Vec<u8> = vec![0x6C, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x30FF, 0x21);
+    cpu.write(0x3000, 0x43);
+    cpu.write(0x3100, 0x99);
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, 0x4321);
+}
+
+#[test]
+fn branch_backwards() {
+    let jump_offset: i8 = -0x10;
+    let program: Vec<u8> = vec![0xD0, jump_offset as u8];
+    let mut cpu = CPU::new(program);
+
+    let expected_branch_location = cpu.program_counter + 2 - 0x10;
+
+    cpu.run_next_instruction();
+    assert_eq!(cpu.program_counter, expected_branch_location);
 }
 
 #[test]
 fn jsr() {
-    let program: Vec<u8> = vec![];
+    let subroutine_addr: u16 = 0x9000;
+    let program: Vec<u8> = vec![0x20, 0x00, 0x90];
+    let mut cpu = CPU::new(program);
+    let jsr_addr = cpu.program_counter;
 
+    cpu.run_next_instruction();
 
-    assert!(false);
+    // JSR pushes the address of its own last byte
+    let stack_addr = cpu.get_stack_memory_addr();
+    assert_eq!(cpu.program_counter, subroutine_addr);
+    assert_eq!(cpu.load16(stack_addr + 1), jsr_addr + 2);
 }
 
 #[test]
 fn rts() {
-    assert!(false);
-}
\ No newline at end of file
+    let program: Vec<u8> = vec![0x20, 0x04, 0x80, 0xEA, 0x60];
+    let mut cpu = CPU::new(program);
+    let jsr_addr = cpu.program_counter;
+
+    cpu.run_next_instruction();
+    assert_eq!(cpu.program_counter, jsr_addr + 4);
+
+    cpu.run_next_instruction();
+    assert_eq!(cpu.program_counter, jsr_addr + 3);
+    assert_eq!(cpu.stack_pointer, INITIAL_STACK_VALUE);
+}
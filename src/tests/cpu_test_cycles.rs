@@ -0,0 +1,117 @@
+use crate::cpu::*;
+
+#[test]
+fn base_cycle_counts() {
+    // LDA #$01, STA $10, INC $10, JMP $9000
+    let program: Vec<u8> = vec![0xA9, 0x01, 0x85, 0x10, 0xE6, 0x10, 0x4C, 0x00, 0x90];
+    let mut cpu = CPU::new(program);
+
+    assert_eq!(cpu.run_next_instruction(), 2);
+    assert_eq!(cpu.run_next_instruction(), 3);
+    assert_eq!(cpu.run_next_instruction(), 5);
+    assert_eq!(cpu.run_next_instruction(), 3);
+
+    assert_eq!(cpu.cycles, 13);
+}
+
+#[test]
+fn indexed_read_no_page_cross() {
+    let program: Vec<u8> = vec![0xBD, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0xFF;
+
+    assert_eq!(cpu.run_next_instruction(), 4);
+}
+
+#[test]
+fn indexed_read_page_cross() {
+    let program: Vec<u8> = vec![0xBD, 0x01, 0x30, 0xB9, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0xFF;
+    cpu.registers.y = 0x01;
+
+    assert_eq!(cpu.run_next_instruction(), 5);
+    assert_eq!(cpu.run_next_instruction(), 5);
+}
+
+#[test]
+fn indirect_y_page_cross() {
+    let program: Vec<u8> = vec![0xB1, 0x20, 0xB1, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.write16(0x0020, 0x30F0);
+
+    cpu.registers.y = 0x0F;
+    assert_eq!(cpu.run_next_instruction(), 5);
+
+    cpu.registers.y = 0x10;
+    assert_eq!(cpu.run_next_instruction(), 6);
+}
+
+#[test]
+fn indexed_store_has_no_page_cross_penalty() {
+    let program: Vec<u8> = vec![0x9D, 0x01, 0x30, 0x9D, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0xFF;
+
+    // Stores always take the extra cycle, crossing a page or not
+    assert_eq!(cpu.run_next_instruction(), 5);
+    assert_eq!(cpu.run_next_instruction(), 5);
+}
+
+#[test]
+fn branch_not_taken() {
+    let program: Vec<u8> = vec![0xD0, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.status = ZERO;
+
+    assert_eq!(cpu.run_next_instruction(), 2);
+}
+
+#[test]
+fn branch_taken() {
+    let program: Vec<u8> = vec![0xD0, 0x10];
+    let mut cpu = CPU::new(program);
+
+    assert_eq!(cpu.run_next_instruction(), 3);
+}
+
+#[test]
+fn branch_taken_page_cross() {
+    // Branching backwards from $8002 lands on $7FF0
+    let program: Vec<u8> = vec![0xD0, -0x12_i8 as u8];
+    let mut cpu = CPU::new(program);
+
+    assert_eq!(cpu.run_next_instruction(), 4);
+    assert_eq!(cpu.program_counter, 0x7FF0);
+}
+
+#[test]
+fn interrupt_takes_seven_cycles() {
+    let program: Vec<u8> = vec![0xEA];
+    let mut cpu = CPU::new(program);
+    cpu.write16(NMI_VECTOR, 0x9000);
+
+    cpu.trigger_nmi();
+
+    assert_eq!(cpu.run_next_instruction(), INTERRUPT_CYCLES);
+    assert_eq!(cpu.cycles, INTERRUPT_CYCLES as u64);
+}
+
+#[test]
+fn run_for_cycles() {
+    let program: Vec<u8> = vec![0xEA; 16];
+    let mut cpu = CPU::new(program);
+
+    assert_eq!(cpu.run_for_cycles(10), 10);
+    assert_eq!(cpu.program_counter, PROGRAM_START_ADDR + 5);
+}
+
+#[test]
+fn run_for_cycles_overshoots_by_partial_instruction() {
+    // Three cycle instruction can't be split, so asking for 4 runs 5
+    let program: Vec<u8> = vec![0xA9, 0x01, 0x85, 0x10, 0xEA];
+    let mut cpu = CPU::new(program);
+
+    assert_eq!(cpu.run_for_cycles(4), 5);
+    assert_eq!(cpu.cycles, 5);
+}
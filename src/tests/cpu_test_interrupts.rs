@@ -0,0 +1,133 @@
+use crate::cpu::*;
+
+const NMI_HANDLER: u16 = 0x9000;
+const IRQ_HANDLER: u16 = 0xA000;
+
+fn create_cpu_with_handlers() -> CPU {
+    let program: Vec<u8> = vec![0xEA, 0xEA];
+    let mut cpu = CPU::new(program);
+    cpu.write16(NMI_VECTOR, NMI_HANDLER);
+    cpu.write16(IRQ_VECTOR, IRQ_HANDLER);
+
+    cpu
+}
+
+#[test]
+fn nmi() {
+    let mut cpu = create_cpu_with_handlers();
+    let return_addr = cpu.program_counter;
+
+    cpu.trigger_nmi();
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, NMI_HANDLER);
+    assert_eq!(cpu.status, INTERRUPT_DISABLE);
+    assert!(!cpu.nmi_pending);
+
+    // Hardware interrupts push the status without the break flag
+    let stack_addr = cpu.get_stack_memory_addr();
+    assert_eq!(cpu.peep_stack(), UNUSED);
+    assert_eq!(cpu.load16(stack_addr + 2), return_addr);
+}
+
+#[test]
+fn nmi_ignores_interrupt_disable() {
+    let mut cpu = create_cpu_with_handlers();
+    cpu.status = INTERRUPT_DISABLE;
+
+    cpu.trigger_nmi();
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, NMI_HANDLER);
+}
+
+#[test]
+fn nmi_is_only_taken_once() {
+    let mut cpu = create_cpu_with_handlers();
+    cpu.write(NMI_HANDLER, 0xEA);
+
+    cpu.trigger_nmi();
+    cpu.run_next_instruction();
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, NMI_HANDLER + 1);
+}
+
+#[test]
+fn irq() {
+    let mut cpu = create_cpu_with_handlers();
+    let return_addr = cpu.program_counter;
+
+    cpu.set_irq_line(true);
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, IRQ_HANDLER);
+    assert_eq!(cpu.status, INTERRUPT_DISABLE);
+
+    let stack_addr = cpu.get_stack_memory_addr();
+    assert_eq!(cpu.peep_stack(), UNUSED);
+    assert_eq!(cpu.load16(stack_addr + 2), return_addr);
+}
+
+#[test]
+fn irq_masked_by_interrupt_disable() {
+    let mut cpu = create_cpu_with_handlers();
+    cpu.status = INTERRUPT_DISABLE;
+    let start = cpu.program_counter;
+
+    cpu.set_irq_line(true);
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, start + 1);
+}
+
+#[test]
+fn nmi_has_priority_over_irq() {
+    let mut cpu = create_cpu_with_handlers();
+
+    cpu.set_irq_line(true);
+    cpu.trigger_nmi();
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, NMI_HANDLER);
+
+    // The NMI handler is running with interrupts disabled so the IRQ has to wait
+    cpu.write(NMI_HANDLER, 0x40);
+    cpu.run_next_instruction();
+
+    // RTI restores the interrupt disable flag, so now the IRQ can go through
+    cpu.run_next_instruction();
+    assert_eq!(cpu.program_counter, IRQ_HANDLER);
+}
+
+#[test]
+fn irq_handler_returns_with_rti() {
+    let mut cpu = create_cpu_with_handlers();
+    cpu.write(IRQ_HANDLER, 0x40);
+    let return_addr = cpu.program_counter;
+    cpu.status = CARRY;
+
+    cpu.set_irq_line(true);
+    cpu.run_next_instruction();
+    cpu.set_irq_line(false);
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, return_addr);
+    assert_eq!(cpu.status, CARRY);
+    assert_eq!(cpu.stack_pointer, INITIAL_STACK_VALUE);
+}
+
+#[test]
+fn brk_returns_past_padding_byte() {
+    let program: Vec<u8> = vec![0x00, 0xFF, 0xEA];
+    let mut cpu = CPU::new(program);
+    cpu.write16(IRQ_VECTOR, IRQ_HANDLER);
+    cpu.write(IRQ_HANDLER, 0x40);
+    let start = cpu.program_counter;
+
+    cpu.run_next_instruction();
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, start + 2);
+    assert_eq!(cpu.status, 0);
+}
@@ -23,7 +23,7 @@ fn php() {
 
     cpu.run_next_instruction();
 
-    assert_eq!(cpu.peep_stack(), test_status);
+    assert_eq!(cpu.peep_stack(), test_status | BREAK | UNUSED);
 }
 
 #[test]
@@ -32,8 +32,8 @@ fn pla() {
 
     let program: Vec<u8> = vec![0x68];
     let mut cpu = CPU::new(program);
+    cpu.write(cpu.get_stack_memory_addr(), test_value);
     cpu.stack_pointer -= 1;
-    cpu.memory[cpu.get_stack_memory_addr() as usize] = test_value;
 
     cpu.run_next_instruction();
 
@@ -46,8 +46,8 @@ fn plp() {
 
     let program: Vec<u8> = vec![0x28];
     let mut cpu = CPU::new(program);
+    cpu.write(cpu.get_stack_memory_addr(), test_status);
     cpu.stack_pointer -= 1;
-    cpu.memory[cpu.get_stack_memory_addr() as usize] = test_status;
 
     cpu.run_next_instruction();
 
@@ -80,5 +80,38 @@ fn txs() {
 
 #[test]
 fn brk() {
-    assert!(false);
-}
\ No newline at end of file
+    let handler_addr: u16 = 0x9000;
+    let program: Vec<u8> = vec![0x00, 0xEA];
+    let mut cpu = CPU::new(program);
+    cpu.write16(IRQ_VECTOR, handler_addr);
+    cpu.status = CARRY;
+
+    let return_addr = cpu.program_counter + 2;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.program_counter, handler_addr);
+    assert_eq!(cpu.status, CARRY | INTERRUPT_DISABLE);
+
+    // Status is on top, followed by the low and then high byte of the return address
+    let stack_addr = cpu.get_stack_memory_addr();
+    assert_eq!(cpu.peep_stack(), CARRY | BREAK | UNUSED);
+    assert_eq!(cpu.load16(stack_addr + 2), return_addr);
+}
+
+#[test]
+fn stack_pointer_points_at_next_free_slot() {
+    let program: Vec<u8> = vec![0x00];
+    let mut cpu = CPU::new(program);
+    cpu.write16(IRQ_VECTOR, 0x9000);
+    cpu.stack_pointer = 0xFF;
+
+    let return_addr = cpu.program_counter + 2;
+
+    cpu.run_next_instruction();
+
+    // TSX; LDA $0101,X is how a handler finds the pushed status
+    assert_eq!(cpu.stack_pointer, 0xFC);
+    assert_eq!(cpu.load(0x01FD), BREAK | UNUSED);
+    assert_eq!(cpu.load16(0x01FE), return_addr);
+}
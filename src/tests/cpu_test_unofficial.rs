@@ -0,0 +1,419 @@
+use crate::cpu::*;
+
+#[test]
+fn nop_implied() {
+    let program: Vec<u8> = vec![0x1A];
+    let mut cpu = CPU::new(program);
+    let counter = cpu.program_counter;
+
+    assert_eq!(cpu.run_next_instruction(), 2);
+    assert_eq!(cpu.program_counter, counter + 1);
+}
+
+#[test]
+fn nop_immediate() {
+    let program: Vec<u8> = vec![0x80, 0xFF];
+    let mut cpu = CPU::new(program);
+    let counter = cpu.program_counter;
+
+    assert_eq!(cpu.run_next_instruction(), 2);
+    assert_eq!(cpu.program_counter, counter + 2);
+}
+
+#[test]
+fn nop_zero_page_x() {
+    let program: Vec<u8> = vec![0x14, 0xFF];
+    let mut cpu = CPU::new(program);
+    let counter = cpu.program_counter;
+
+    assert_eq!(cpu.run_next_instruction(), 4);
+    assert_eq!(cpu.program_counter, counter + 2);
+}
+
+#[test]
+fn nop_absolute_x_page_cross() {
+    let program: Vec<u8> = vec![0x1C, 0xFF, 0x30, 0x1C, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x01;
+    let counter = cpu.program_counter;
+
+    assert_eq!(cpu.run_next_instruction(), 5);
+    assert_eq!(cpu.run_next_instruction(), 4);
+    assert_eq!(cpu.program_counter, counter + 6);
+    assert_eq!(cpu.status, 0);
+}
+
+#[test]
+fn lax() {
+    let program: Vec<u8> = vec![0xA7, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x92);
+
+    assert_eq!(cpu.run_next_instruction(), 3);
+
+    assert_eq!(cpu.registers.a, 0x92);
+    assert_eq!(cpu.registers.x, 0x92);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn lax_absolute_y_page_cross() {
+    let program: Vec<u8> = vec![0xBF, 0xFF, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.y = 0x01;
+    cpu.write(0x3100, 0x42);
+
+    assert_eq!(cpu.run_next_instruction(), 5);
+    assert_eq!(cpu.registers.x, 0x42);
+}
+
+#[test]
+fn sax() {
+    let program: Vec<u8> = vec![0x87, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0xF3;
+    cpu.registers.x = 0x3F;
+    cpu.status = NEGATIVE;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x10), 0x33);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn sbc_unofficial() {
+    let program: Vec<u8> = vec![0xEB, 0x01];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x05;
+    cpu.status = CARRY;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x04);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn dcp() {
+    let program: Vec<u8> = vec![0xC7, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x43);
+    cpu.registers.a = 0x42;
+
+    assert_eq!(cpu.run_next_instruction(), 5);
+
+    assert_eq!(cpu.load(0x10), 0x42);
+    assert_eq!(cpu.status, ZERO | CARRY);
+}
+
+#[test]
+fn isb() {
+    let program: Vec<u8> = vec![0xE7, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x0F);
+    cpu.registers.a = 0x20;
+    cpu.status = CARRY;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x10), 0x10);
+    assert_eq!(cpu.registers.a, 0x10);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn slo() {
+    let program: Vec<u8> = vec![0x07, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x81);
+    cpu.registers.a = 0x01;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x10), 0x02);
+    assert_eq!(cpu.registers.a, 0x03);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn rla() {
+    let program: Vec<u8> = vec![0x27, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x81);
+    cpu.registers.a = 0xFF;
+    cpu.status = CARRY;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x10), 0x03);
+    assert_eq!(cpu.registers.a, 0x03);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn sre() {
+    let program: Vec<u8> = vec![0x47, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x03);
+    cpu.registers.a = 0x81;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x10), 0x01);
+    assert_eq!(cpu.registers.a, 0x80);
+    assert_eq!(cpu.status, CARRY | NEGATIVE);
+}
+
+#[test]
+fn rra() {
+    let program: Vec<u8> = vec![0x67, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x10, 0x03);
+    cpu.registers.a = 0x10;
+
+    cpu.run_next_instruction();
+
+    // ROR leaves 0x01 with the carry set, which then gets added in
+    assert_eq!(cpu.load(0x10), 0x01);
+    assert_eq!(cpu.registers.a, 0x12);
+    assert_eq!(cpu.status, 0);
+}
+
+#[test]
+fn rmw_indirect_y_cycles() {
+    let program: Vec<u8> = vec![0x13, 0x20];
+    let mut cpu = CPU::new(program);
+    cpu.write16(0x0020, 0x30FF);
+    cpu.registers.y = 0x01;
+
+    // Read-modify-write never pays extra for crossing a page
+    assert_eq!(cpu.run_next_instruction(), 8);
+}
+
+#[test]
+fn anc() {
+    let program: Vec<u8> = vec![0x0B, 0x80];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0xFF;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x80);
+    assert_eq!(cpu.status, NEGATIVE | CARRY);
+}
+
+#[test]
+fn alr() {
+    let program: Vec<u8> = vec![0x4B, 0x03];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0xFF;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x01);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn arr() {
+    let program: Vec<u8> = vec![0x6B, 0xFF, 0x6B, 0xFF];
+    let mut cpu = CPU::new(program);
+
+    // 0xC0 rotated with carry in is 0xE0, bit 6 set and bit 5 set
+    cpu.registers.a = 0xC0;
+    cpu.status = CARRY;
+    cpu.run_next_instruction();
+    assert_eq!(cpu.registers.a, 0xE0);
+    assert_eq!(cpu.status, NEGATIVE | CARRY);
+
+    // 0x80 rotated is 0x40, bit 6 set and bit 5 clear
+    cpu.registers.a = 0x80;
+    cpu.status = 0;
+    cpu.run_next_instruction();
+    assert_eq!(cpu.registers.a, 0x40);
+    assert_eq!(cpu.status, CARRY | OVERFLOW);
+}
+
+#[test]
+fn axs() {
+    let program: Vec<u8> = vec![0xCB, 0x02];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x0F;
+    cpu.registers.x = 0xFC;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.x, 0x0A);
+    assert_eq!(cpu.status, CARRY);
+}
+
+#[test]
+fn las() {
+    let program: Vec<u8> = vec![0xBB, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.write(0x3000, 0xF0);
+    cpu.stack_pointer = 0x3F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x30);
+    assert_eq!(cpu.registers.x, 0x30);
+    assert_eq!(cpu.stack_pointer, 0x30);
+}
+
+#[test]
+fn xaa() {
+    let program: Vec<u8> = vec![0x8B, 0x3C];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x01;
+    cpu.registers.x = 0xF5;
+
+    cpu.run_next_instruction();
+
+    // (0x01 | 0xEE) & 0xF5 & 0x3C
+    assert_eq!(cpu.registers.a, 0x24);
+}
+
+#[test]
+fn lxa() {
+    let program: Vec<u8> = vec![0xAB, 0x93];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0x00;
+    cpu.registers.x = 0x00;
+    cpu.status = 0;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.registers.a, 0x82);
+    assert_eq!(cpu.registers.x, 0x82);
+    assert_eq!(cpu.status, NEGATIVE);
+}
+
+#[test]
+fn shx() {
+    let program: Vec<u8> = vec![0x9E, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0xFF;
+    cpu.registers.y = 0x04;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x3004), 0x31);
+}
+
+#[test]
+fn shy() {
+    let program: Vec<u8> = vec![0x9C, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x04;
+    cpu.registers.y = 0x1F;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.load(0x3004), 0x11);
+}
+
+#[test]
+fn shx_page_cross_corrupts_high_byte() {
+    let program: Vec<u8> = vec![0x9E, 0xF0, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.x = 0x0F;
+    cpu.registers.y = 0x20;
+
+    cpu.run_next_instruction();
+
+    // 0x0F & 0x31 is 0x01, which also becomes the high byte of $3110
+    assert_eq!(cpu.load(0x0110), 0x01);
+    assert_eq!(cpu.load(0x3110), 0x00);
+}
+
+#[test]
+fn tas() {
+    let program: Vec<u8> = vec![0x9B, 0x00, 0x30];
+    let mut cpu = CPU::new(program);
+    cpu.registers.a = 0xF3;
+    cpu.registers.x = 0x3F;
+    cpu.registers.y = 0x01;
+
+    cpu.run_next_instruction();
+
+    assert_eq!(cpu.stack_pointer, 0x33);
+    assert_eq!(cpu.load(0x3001), 0x31);
+}
+
+#[test]
+fn kil_halts_cpu() {
+    let program: Vec<u8> = vec![0x02, 0xEA];
+    let mut cpu = CPU::new(program);
+    let counter = cpu.program_counter;
+
+    cpu.run_next_instruction();
+    assert!(cpu.halted);
+
+    assert_eq!(cpu.run_next_instruction(), 0);
+    assert_eq!(cpu.program_counter, counter);
+
+    cpu.reset();
+    assert!(!cpu.halted);
+}
+
+#[test]
+fn run_program_stops_on_kil() {
+    let program: Vec<u8> = vec![0xE8, 0x02, 0xE8];
+    let mut cpu = CPU::new(program);
+
+    cpu.run_program();
+
+    assert_eq!(cpu.registers.x, 1);
+}
+
+#[test]
+fn trap_mode_stops_before_executing() {
+    let program: Vec<u8> = vec![0xA7, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.illegal_opcode_mode = IllegalOpcodeMode::Trap;
+    cpu.write(0x10, 0x42);
+    let counter = cpu.program_counter;
+
+    assert_eq!(cpu.run_next_instruction(), 0);
+
+    assert!(cpu.halted);
+    assert_eq!(cpu.program_counter, counter);
+    assert_eq!(cpu.registers.a, 0x00);
+}
+
+#[test]
+fn log_mode_still_executes() {
+    let program: Vec<u8> = vec![0xA7, 0x10];
+    let mut cpu = CPU::new(program);
+    cpu.illegal_opcode_mode = IllegalOpcodeMode::Log;
+    cpu.write(0x10, 0x42);
+
+    cpu.run_next_instruction();
+
+    assert!(!cpu.halted);
+    assert_eq!(cpu.registers.a, 0x42);
+}
+
+#[test]
+fn trap_mode_ignores_official_instructions() {
+    let program: Vec<u8> = vec![0xA9, 0x42];
+    let mut cpu = CPU::new(program);
+    cpu.illegal_opcode_mode = IllegalOpcodeMode::Trap;
+
+    cpu.run_next_instruction();
+
+    assert!(!cpu.halted);
+    assert_eq!(cpu.registers.a, 0x42);
+}
+
+#[test]
+fn every_opcode_decodes() {
+    // Nothing should panic while looking up the addressing mode, name or cycle count
+    for instruction in 0..=0xFFu8 {
+        crate::util::map_instruction_to_addressing_mode(instruction);
+        crate::util::map_instruction_to_name(instruction);
+        crate::util::map_instruction_to_cycle_count(instruction);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bus::*;
+use crate::disassembler::*;
+
+#[test]
+fn immediate() {
+    let instructions = disassemble(&[0xA9, 0x01], 0x8000);
+
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0].text, "LDA #$01");
+    assert_eq!(instructions[0].to_string(), "8000  A9 01     LDA #$01");
+}
+
+#[test]
+fn operand_formats() {
+    let program: Vec<u8> = vec![
+        0x85, 0x10,         // STA $10
+        0xB5, 0x10,         // LDA $10,X
+        0xB6, 0x10,         // LDX $10,Y
+        0xAD, 0x34, 0x12,   // LDA $1234
+        0xBD, 0x34, 0x12,   // LDA $1234,X
+        0xB9, 0x34, 0x12,   // LDA $1234,Y
+        0xA1, 0x20,         // LDA ($20,X)
+        0xB1, 0x20,         // LDA ($20),Y
+        0x6C, 0xFC, 0xFF,   // JMP ($FFFC)
+        0x0A,               // ASL A
+        0xEA                // NOP
+    ];
+
+    let text: Vec<String> = disassemble(&program, 0x8000).into_iter().map(|instruction| instruction.text).collect();
+
+    assert_eq!(text, vec![
+        "STA $10", "LDA $10,X", "LDX $10,Y", "LDA $1234", "LDA $1234,X", "LDA $1234,Y",
+        "LDA ($20,X)", "LDA ($20),Y", "JMP ($FFFC)", "ASL A", "NOP"
+    ]);
+}
+
+#[test]
+fn addresses_advance_by_instruction_length() {
+    let instructions = disassemble(&[0xEA, 0xA9, 0x00, 0x4C, 0x00, 0x80], 0xC000);
+
+    let addresses: Vec<u16> = instructions.iter().map(|instruction| instruction.address).collect();
+    assert_eq!(addresses, vec![0xC000, 0xC001, 0xC003]);
+}
+
+#[test]
+fn branch_targets_are_resolved() {
+    let instructions = disassemble(&[0xD0, 0x04, 0xF0, 0xFC], 0x8000);
+
+    assert_eq!(instructions[0].text, "BNE $8006");
+    assert_eq!(instructions[1].text, "BEQ $8000");
+}
+
+#[test]
+fn truncated_instruction_becomes_data() {
+    let instructions = disassemble(&[0xEA, 0xAD, 0x34], 0x8000);
+
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(instructions[1].text, ".db $AD");
+    assert_eq!(instructions[2].text, ".db $34");
+    assert_eq!(instructions[2].address, 0x8002);
+}
+
+#[test]
+fn from_bus() {
+    let mut bus = FlatBus::new();
+    bus.write(0x8000, 0x20);
+    bus.write(0x8001, 0x00);
+    bus.write(0x8002, 0x90);
+    bus.write(0x8003, 0x60);
+
+    let instructions = disassemble_from_bus(&bus, 0x8000, 2);
+
+    assert_eq!(instructions[0].text, "JSR $9000");
+    assert_eq!(instructions[1].address, 0x8003);
+    assert_eq!(instructions[1].text, "RTS");
+}
+
+#[test]
+fn program_bank_addresses() {
+    // NROM-128 ends up at $C000 where the vectors are, NROM-256 fills both halves
+    assert_eq!(program_bank_address(0, 1), 0xC000);
+    assert_eq!(program_bank_address(0, 2), 0x8000);
+    assert_eq!(program_bank_address(1, 2), 0xC000);
+
+    // Switchable banks all go at $8000 with the last one fixed
+    assert_eq!(program_bank_address(5, 8), 0x8000);
+    assert_eq!(program_bank_address(7, 8), 0xC000);
+}
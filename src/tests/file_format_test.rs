@@ -0,0 +1,381 @@
+use crate::bus::*;
+use crate::file_format::*;
+use crate::ppu::Mirroring;
+use crate::tests::test_util::create_ines_image;
+
+#[test]
+fn ines_sizes() {
+    let rom = INES::from_bytes(create_ines_image(2, 1, 0x00, 0x00)).unwrap();
+
+    assert_eq!(rom.header.format, HeaderFormat::INES);
+    assert_eq!(rom.header.program_rom_size, 32768);
+    assert_eq!(rom.header.char_rom_size, 8192);
+    assert_eq!(rom.program_rom.len(), 32768);
+    assert_eq!(rom.program_rom[16384], 0x01);
+    assert_eq!(rom.char_rom.len(), 8192);
+    assert_eq!(rom.char_rom[0], 0x80);
+    assert!(rom.title.is_none());
+}
+
+#[test]
+fn ines_mapper_number() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0x40, 0x10)).unwrap();
+
+    assert_eq!(rom.header.mapper, 0x14);
+}
+
+#[test]
+fn ines_mirroring() {
+    assert_eq!(INES::from_bytes(create_ines_image(1, 1, 0x00, 0x00)).unwrap().header.mirroring, Mirroring::Horizontal);
+    assert_eq!(INES::from_bytes(create_ines_image(1, 1, 0x01, 0x00)).unwrap().header.mirroring, Mirroring::Vertical);
+
+    let four_screen = INES::from_bytes(create_ines_image(1, 1, 0x09, 0x00)).unwrap();
+    assert!(four_screen.header.four_screen);
+    assert_eq!(four_screen.header.mirroring, Mirroring::FourScreen);
+}
+
+#[test]
+fn ines_battery_and_ram() {
+    let rom = INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap();
+
+    assert!(rom.header.battery);
+    assert_eq!(rom.header.program_nvram_size, 8192);
+    assert_eq!(rom.header.program_ram_size, 0);
+    // No CHR ROM means CHR RAM
+    assert_eq!(rom.header.char_ram_size, 8192);
+    assert!(rom.char_rom.is_empty());
+}
+
+#[test]
+fn ines_tv_system() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image[9] = 0x01;
+
+    assert_eq!(INES::from_bytes(image).unwrap().header.tv_system, TvSystem::PAL);
+}
+
+#[test]
+fn ines_ignores_garbage_at_end_of_header() {
+    let mut image = create_ines_image(1, 1, 0x10, 0x40);
+    image[12..16].copy_from_slice(b"ude!");
+
+    assert_eq!(INES::from_bytes(image).unwrap().header.mapper, 0x01);
+}
+
+#[test]
+fn trainer() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0x04, 0x00)).unwrap();
+
+    assert!(rom.header.is_trainer_present());
+    assert_eq!(rom.trainer.unwrap()[0], 0xEE);
+    // The trainer comes before PRG ROM
+    assert_eq!(rom.program_rom[0], 0x00);
+    assert_eq!(rom.char_rom[0], 0x80);
+}
+
+#[test]
+fn console_type() {
+    assert_eq!(INES::from_bytes(create_ines_image(1, 1, 0x00, 0x01)).unwrap().header.console_type, ConsoleType::VsSystem);
+    assert_eq!(INES::from_bytes(create_ines_image(1, 1, 0x00, 0x02)).unwrap().header.console_type, ConsoleType::PlayChoice10);
+}
+
+#[test]
+fn play_choice_roms() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x02);
+    image.extend(vec![0x11; 8192]);
+    image.extend(vec![0x22; 32]);
+    let rom = INES::from_bytes(image).unwrap();
+
+    assert_eq!(rom.play_choice_inst_rom.unwrap(), vec![0x11; 8192]);
+    assert_eq!(rom.play_choice_prom.unwrap(), vec![0x22; 32]);
+    assert!(rom.title.is_none());
+}
+
+#[test]
+fn play_choice_roms_only_on_play_choice() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image.extend(vec![0x11; 8192 + 32]);
+    let rom = INES::from_bytes(image).unwrap();
+
+    assert!(rom.play_choice_inst_rom.is_none());
+    assert!(rom.play_choice_prom.is_none());
+}
+
+#[test]
+fn play_choice_roms_then_title() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x02);
+    image.extend(vec![0x11; 8192 + 32]);
+    image.extend(b"Test Title");
+    image.extend(vec![0x00; 118]);
+
+    assert_eq!(INES::from_bytes(image).unwrap().title_text().unwrap(), "Test Title");
+}
+
+#[test]
+fn title() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image.extend(b"Test Title");
+    image.extend(vec![0x20; 117]);
+
+    let title = INES::from_bytes(image).unwrap().title.unwrap();
+    assert_eq!(title.len(), 127);
+    assert!(title.starts_with(b"Test Title"));
+}
+
+#[test]
+fn title_text_drops_padding() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image.extend(b"Test Title");
+    image.extend(vec![0x00; 118]);
+
+    assert_eq!(INES::from_bytes(image).unwrap().title_text().unwrap(), "Test Title");
+}
+
+#[test]
+fn padding_is_not_a_title() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image.extend(vec![0xFF; 16]);
+
+    assert!(INES::from_bytes(image).unwrap().title.is_none());
+}
+
+#[test]
+fn header_summary() {
+    assert_eq!(INES::from_bytes(create_ines_image(2, 1, 0x02, 0x00)).unwrap().header.to_string(), "iNES, mapper 0, 32KB PRG, 8KB CHR, battery");
+    assert_eq!(INES::from_bytes(create_ines_image(1, 0, 0x48, 0x08)).unwrap().header.to_string(), "NES 2.0, mapper 4.0, 16KB PRG, CHR RAM, four-screen");
+}
+
+#[test]
+fn nes2_detection() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0x00, 0x08)).unwrap();
+
+    assert_eq!(rom.header.format, HeaderFormat::NES2);
+}
+
+#[test]
+fn nes2_mapper_and_submapper() {
+    let mut image = create_ines_image(1, 1, 0x50, 0x48);
+    image[8] = 0x31;
+
+    let header = INES::from_bytes(image).unwrap().header;
+
+    assert_eq!(header.mapper, 0x145);
+    assert_eq!(header.submapper, 3);
+}
+
+#[test]
+fn nes2_rom_size_high_bits() {
+    let mut image = create_ines_image(0, 0, 0x00, 0x08);
+    // 0x100 PRG banks would be 4MB, so use a small CHR size instead and no PRG
+    image[9] = 0x10;
+    image.extend(vec![0; 0x100 * 8192]);
+
+    let rom = INES::from_bytes(image).unwrap();
+
+    assert_eq!(rom.header.char_rom_size, 0x100 * 8192);
+    assert_eq!(rom.char_rom.len(), 0x100 * 8192);
+}
+
+#[test]
+fn nes2_exponent_multiplier_size() {
+    let mut image = create_ines_image(0, 0, 0x00, 0x08);
+    // 2^10 * (1*2 + 1) = 3072 bytes of PRG
+    image[4] = (10 << 2) | 0x01;
+    image[9] = 0x0F;
+    image.extend(vec![0x42; 3072]);
+
+    let rom = INES::from_bytes(image).unwrap();
+
+    assert_eq!(rom.header.program_rom_size, 3072);
+    assert_eq!(rom.program_rom.len(), 3072);
+}
+
+#[test]
+fn nes2_ram_sizes() {
+    let mut image = create_ines_image(1, 0, 0x02, 0x08);
+    image[10] = 0x70;
+    image[11] = 0x07;
+
+    let header = INES::from_bytes(image).unwrap().header;
+
+    assert_eq!(header.program_ram_size, 0);
+    assert_eq!(header.program_nvram_size, 8192);
+    assert_eq!(header.char_ram_size, 8192);
+    assert_eq!(header.char_nvram_size, 0);
+}
+
+#[test]
+fn nes2_tv_system() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x08);
+    image[12] = 0x03;
+
+    assert_eq!(INES::from_bytes(image).unwrap().header.tv_system, TvSystem::Dendy);
+}
+
+#[test]
+fn trainer_is_loaded_at_0x7000() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0x04, 0x00)).unwrap();
+    let mut bus = NESBus::new();
+
+    bus.load_rom(&rom).unwrap();
+
+    assert_eq!(bus.read(TRAINER_ADDRESS), 0xEE);
+    assert_eq!(bus.read(TRAINER_ADDRESS + 511), 0xEE);
+    assert_eq!(bus.read(TRAINER_ADDRESS + 512), 0x00);
+}
+
+#[test]
+fn single_bank_is_mirrored() {
+    let mut image = create_ines_image(1, 1, 0x01, 0x00);
+    image[16 + 0x3FFC] = 0x34;
+
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(image).unwrap()).unwrap();
+
+    assert_eq!(bus.read(0xFFFC), 0x34);
+    assert_eq!(bus.read(0xBFFC), 0x34);
+    assert_eq!(bus.ppu.read(0x0000), 0x80);
+    assert_eq!(bus.ppu.nametable_mirroring(), Mirroring::Vertical);
+}
+
+#[test]
+fn two_banks_fill_cartridge_space() {
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(create_ines_image(2, 1, 0x00, 0x00)).unwrap()).unwrap();
+
+    assert_eq!(bus.read(0x8000), 0x00);
+    assert_eq!(bus.read(0xC000), 0x01);
+}
+
+#[test]
+fn truncated_header() {
+    assert_eq!(INES::from_bytes(vec![b'N', b'E', b'S', 0x1A, 1]).err(), Some(RomError::TruncatedHeader));
+    assert_eq!(INES::from_bytes(Vec::new()).err(), Some(RomError::TruncatedHeader));
+}
+
+#[test]
+fn bad_magic() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image[3] = 0x00;
+
+    assert_eq!(INES::from_bytes(image).err(), Some(RomError::BadMagic));
+}
+
+#[test]
+fn truncated_trainer() {
+    let mut image = create_ines_image(0, 0, 0x04, 0x00);
+    image.truncate(100);
+
+    assert_eq!(INES::from_bytes(image).err(), Some(RomError::TruncatedTrainer));
+}
+
+#[test]
+fn truncated_program_rom() {
+    let mut image = create_ines_image(2, 1, 0x00, 0x00);
+    image.truncate(16 + 20000);
+
+    assert_eq!(INES::from_bytes(image).err(), Some(RomError::TruncatedProgramRom { expected: 32768, found: 20000 }));
+}
+
+#[test]
+fn truncated_char_rom() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image.pop();
+
+    assert_eq!(INES::from_bytes(image).err(), Some(RomError::TruncatedCharRom { expected: 8192, found: 8191 }));
+}
+
+#[test]
+fn huge_exponent_size_is_truncated_not_a_panic() {
+    let mut image = create_ines_image(0, 0, 0x00, 0x08);
+    image[4] = 0xFF;
+    image[9] = 0x0F;
+
+    assert!(matches!(INES::from_bytes(image), Err(RomError::TruncatedProgramRom { .. })));
+}
+
+#[test]
+fn unsupported_mapper() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0xF0, 0x00)).unwrap();
+    let mut bus = NESBus::new();
+
+    assert_eq!(bus.load_rom(&rom).err(), Some(RomError::UnsupportedMapper(0x0F)));
+}
+
+#[test]
+fn errors_are_readable() {
+    assert_eq!(RomError::UnsupportedMapper(4).to_string(), "Mapper 4 is not supported");
+}
+
+fn temp_rom_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("nes_emulator_file_format_test_{}", name))
+}
+
+#[test]
+fn from_file() {
+    let path = temp_rom_path("plain.nes");
+    std::fs::write(&path, create_ines_image(1, 1, 0x00, 0x00)).unwrap();
+
+    let rom = INES::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(rom.program_rom.len(), 16384);
+}
+
+#[test]
+fn from_gzip_file() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let path = temp_rom_path("compressed.nes.gz");
+    let mut encoder = GzEncoder::new(std::fs::File::create(&path).unwrap(), Compression::default());
+    encoder.write_all(&create_ines_image(1, 1, 0x01, 0x00)).unwrap();
+    encoder.finish().unwrap();
+
+    let rom = INES::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(rom.header.mirroring, Mirroring::Vertical);
+    assert_eq!(rom.char_rom[0], 0x80);
+}
+
+#[test]
+fn from_zip_file() {
+    use std::io::Write;
+
+    let path = temp_rom_path("archive.zip");
+    let mut archive = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    archive.start_file("readme.txt", options).unwrap();
+    archive.write_all(b"not a rom").unwrap();
+    archive.start_file("Game.NES", options).unwrap();
+    archive.write_all(&create_ines_image(2, 0, 0x00, 0x00)).unwrap();
+    archive.finish().unwrap();
+
+    let rom = INES::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(rom.program_rom.len(), 32768);
+}
+
+#[test]
+fn zip_without_rom() {
+    use std::io::Write;
+
+    let path = temp_rom_path("empty.zip");
+    let mut archive = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    archive.start_file("readme.txt", zip::write::FileOptions::default()).unwrap();
+    archive.write_all(b"not a rom").unwrap();
+    archive.finish().unwrap();
+
+    let result = INES::from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.err(), Some(RomError::NoRomInArchive));
+}
+
+#[test]
+fn missing_file() {
+    assert!(matches!(INES::from_file(&temp_rom_path("does_not_exist.nes")), Err(RomError::Io(_))));
+}
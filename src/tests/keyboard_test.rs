@@ -0,0 +1,30 @@
+use minifb::Key;
+
+use crate::controller::buttons::*;
+use crate::graphics::keyboard::KeyMapping;
+
+#[test]
+fn default_key_mapping() {
+    let mapping = KeyMapping::new();
+
+    assert_eq!(mapping.buttons(&[Key::X, Key::Enter, Key::Left]), [BUTTON_A | BUTTON_START | BUTTON_LEFT, 0]);
+    assert_eq!(mapping.buttons(&[Key::Q]), [0, 0]);
+}
+
+#[test]
+fn key_mapping_from_json() {
+    let mapping = KeyMapping::from_json(r#"{ "player_1": { "A": "space" }, "player_2": { "Start": "NumPad5", "up": "W" } }"#).unwrap();
+
+    assert_eq!(mapping.buttons(&[Key::Space]), [BUTTON_A, 0]);
+    // Rebinding A took it off X
+    assert_eq!(mapping.buttons(&[Key::X]), [0, 0]);
+    assert_eq!(mapping.buttons(&[Key::NumPad5, Key::W, Key::Z]), [BUTTON_B, BUTTON_START | BUTTON_UP]);
+}
+
+#[test]
+fn key_mapping_errors() {
+    assert!(KeyMapping::from_json(r#"{ "player_3": { "A": "X" } }"#).is_err());
+    assert!(KeyMapping::from_json(r#"{ "player_1": { "Turbo": "X" } }"#).is_err());
+    assert!(KeyMapping::from_json(r#"{ "player_1": { "A": "NotAKey" } }"#).is_err());
+    assert!(KeyMapping::from_json("not json").is_err());
+}
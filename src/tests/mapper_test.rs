@@ -0,0 +1,599 @@
+use crate::bus::*;
+use crate::cpu::CPU;
+use crate::file_format::*;
+use crate::mappers::mapper::*;
+use crate::ppu::Mirroring;
+use crate::tests::test_util::create_ines_image;
+
+fn create_cartridge(program_banks: u8, char_banks: u8, flags_6: u8) -> Cartridge {
+    create_mapper(&INES::from_bytes(create_ines_image(program_banks, char_banks, flags_6, 0x00)).unwrap()).unwrap()
+}
+
+// Same but with an NES 2.0 header so there is somewhere to put the submapper
+fn create_cartridge_with_submapper(program_banks: u8, char_banks: u8, flags_6: u8, submapper: u8) -> Cartridge {
+    let mut image = create_ines_image(program_banks, char_banks, flags_6, 0x08);
+    image[8] = submapper << 4;
+
+    create_mapper(&INES::from_bytes(image).unwrap()).unwrap()
+}
+
+#[test]
+fn nrom_128_is_mirrored() {
+    let cartridge = create_cartridge(1, 1, 0x00);
+    let cartridge = cartridge.borrow();
+
+    assert_eq!(cartridge.cpu_peek(0x8000), 0x00);
+    assert_eq!(cartridge.cpu_peek(0xC000), 0x00);
+    assert_eq!(cartridge.cpu_peek(0xFFFF), 0x00);
+}
+
+#[test]
+fn nrom_256_fills_cartridge_space() {
+    let cartridge = create_cartridge(2, 1, 0x00);
+    let cartridge = cartridge.borrow();
+
+    assert_eq!(cartridge.cpu_peek(0xBFFF), 0x00);
+    assert_eq!(cartridge.cpu_peek(0xC000), 0x01);
+    assert_eq!(cartridge.cpu_peek(0xFFFF), 0x01);
+}
+
+#[test]
+fn nrom_program_rom_is_read_only() {
+    let cartridge = create_cartridge(2, 1, 0x00);
+    cartridge.borrow_mut().cpu_write(0xC000, 0x55);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 0x01);
+}
+
+#[test]
+fn nrom_program_ram() {
+    let cartridge = create_cartridge(1, 1, 0x00);
+    cartridge.borrow_mut().cpu_write(0x6000, 0x12);
+    cartridge.borrow_mut().cpu_write(0x7FFF, 0x34);
+
+    assert_eq!(cartridge.borrow_mut().cpu_read(0x6000), 0x12);
+    assert_eq!(cartridge.borrow_mut().cpu_read(0x7FFF), 0x34);
+}
+
+#[test]
+fn nrom_char_rom_is_read_only() {
+    let cartridge = create_cartridge(1, 1, 0x00);
+    cartridge.borrow_mut().ppu_write(0x0010, 0x55);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x0010), 0x80);
+    assert_eq!(cartridge.borrow().ppu_read(0x1FFF), 0x80);
+}
+
+#[test]
+fn nrom_char_ram_without_char_rom() {
+    let cartridge = create_cartridge(1, 0, 0x00);
+    cartridge.borrow_mut().ppu_write(0x1234, 0x55);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x1234), 0x55);
+}
+
+#[test]
+fn nrom_mirroring_comes_from_header() {
+    assert_eq!(create_cartridge(1, 1, 0x00).borrow().mirroring(), Mirroring::Horizontal);
+    assert_eq!(create_cartridge(1, 1, 0x01).borrow().mirroring(), Mirroring::Vertical);
+    assert!(!create_cartridge(1, 1, 0x00).borrow().irq());
+}
+
+#[test]
+fn unknown_mapper_is_an_error() {
+    let rom = INES::from_bytes(create_ines_image(1, 1, 0x50, 0x00)).unwrap();
+
+    assert_eq!(create_mapper(&rom).err(), Some(RomError::UnsupportedMapper(5)));
+}
+
+#[test]
+fn ppu_uses_cartridge_chr_and_mirroring() {
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(create_ines_image(1, 0, 0x01, 0x00)).unwrap()).unwrap();
+
+    // CHR RAM written through PPUADDR/PPUDATA ends up on the cartridge
+    bus.write(0x2006, 0x00);
+    bus.write(0x2006, 0x20);
+    bus.write(0x2007, 0x66);
+    assert_eq!(bus.ppu.read(0x0020), 0x66);
+    assert_eq!(bus.ppu.chr[0x0020], 0x00);
+
+    // Vertical mirroring from the header, so $2800 is $2000
+    bus.ppu.write(0x2000, 0x77);
+    assert_eq!(bus.ppu.read(0x2800), 0x77);
+}
+
+// One STA to the serial port, which takes 4 cycles
+fn mmc1_serial_write(cartridge: &Cartridge, addr: u16, data: u8) {
+    cartridge.borrow_mut().cpu_write(addr, data);
+    for _ in 0..4 {
+        cartridge.borrow_mut().cpu_cycle();
+    }
+}
+
+// MMC1 registers get written a bit at a time, lowest bit first
+fn mmc1_write(cartridge: &Cartridge, addr: u16, data: u8) {
+    for bit in 0..5 {
+        mmc1_serial_write(cartridge, addr, (data >> bit) & 0x01);
+    }
+}
+
+#[test]
+fn mmc1_powers_on_with_last_bank_fixed() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 7);
+}
+
+#[test]
+fn mmc1_switches_bank_at_8000() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+    mmc1_write(&cartridge, 0xE000, 5);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 7);
+}
+
+#[test]
+fn mmc1_first_bank_fixed_mode() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b0_1000);
+    mmc1_write(&cartridge, 0xE000, 3);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 3);
+}
+
+#[test]
+fn mmc1_32k_mode_ignores_low_bit() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b0_0000);
+    mmc1_write(&cartridge, 0xE000, 5);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 4);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 5);
+}
+
+#[test]
+fn mmc1_reset_bit_clears_shift_register() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b0_1000);
+
+    // Half a write, then a reset, then a full write
+    mmc1_serial_write(&cartridge, 0xE000, 1);
+    mmc1_serial_write(&cartridge, 0xE000, 1);
+    mmc1_serial_write(&cartridge, 0xE000, 0x80);
+    mmc1_write(&cartridge, 0xE000, 2);
+
+    // The reset also put it back into the last bank fixed mode
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 2);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 7);
+}
+
+#[test]
+fn mmc1_register_picked_by_last_write() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+
+    // Only the address of the fifth write matters
+    for _ in 0..4 {
+        mmc1_serial_write(&cartridge, 0x8000, 1);
+    }
+    mmc1_serial_write(&cartridge, 0xE000, 0);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 15 % 8);
+}
+
+#[test]
+fn mmc1_chr_8k_mode() {
+    let cartridge = create_cartridge(2, 2, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b0_1100);
+    mmc1_write(&cartridge, 0xA000, 3);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x81);
+    assert_eq!(cartridge.borrow().ppu_read(0x1000), 0x81);
+}
+
+#[test]
+fn mmc1_chr_4k_mode() {
+    let cartridge = create_cartridge(2, 2, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b1_1100);
+    mmc1_write(&cartridge, 0xA000, 2);
+    mmc1_write(&cartridge, 0xC000, 1);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x81);
+    assert_eq!(cartridge.borrow().ppu_read(0x1000), 0x80);
+}
+
+#[test]
+fn mmc1_chr_ram_is_banked() {
+    let cartridge = create_cartridge(2, 0, 0x10);
+    mmc1_write(&cartridge, 0x8000, 0b1_1100);
+    mmc1_write(&cartridge, 0xC000, 0);
+    cartridge.borrow_mut().ppu_write(0x1000, 0x42);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x42);
+}
+
+#[test]
+fn mmc1_mirroring() {
+    let cartridge = create_cartridge(2, 2, 0x10);
+
+    let modes = [Mirroring::SingleScreenLower, Mirroring::SingleScreenUpper, Mirroring::Vertical, Mirroring::Horizontal];
+    for (control, mirroring) in modes.iter().enumerate() {
+        mmc1_write(&cartridge, 0x8000, 0b0_1100 | control as u8);
+        assert_eq!(cartridge.borrow().mirroring(), *mirroring);
+    }
+}
+
+#[test]
+fn mmc1_ignores_write_on_next_cycle() {
+    let cartridge = create_cartridge(8, 2, 0x10);
+
+    // Two bits written back to back only count once
+    for _ in 0..4 {
+        cartridge.borrow_mut().cpu_write(0xE000, 1);
+        cartridge.borrow_mut().cpu_cycle();
+        cartridge.borrow_mut().cpu_write(0xE000, 0);
+        cartridge.borrow_mut().cpu_cycle();
+        cartridge.borrow_mut().cpu_cycle();
+    }
+    mmc1_serial_write(&cartridge, 0xE000, 0);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0b0_1111 % 8);
+}
+
+#[test]
+fn mmc1_reset_with_read_modify_write() {
+    // In 32KB mode $F000 is in bank 1, make it $FF so the value INC writes back first 
+    //  has bit 7 set
+    let mut image = create_ines_image(8, 2, 0x10, 0x00);
+    image[HEADER_SIZE + PROGRAM_ROM_BANK_SIZE + 0x3000] = 0xFF;
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(image).unwrap()).unwrap();
+    let mut cpu = CPU::with_bus(bus);
+
+    // Put it in 32KB mode, then half a write that the reset should throw away
+    mmc1_write(bus_cartridge(&cpu), 0x8000, 0b0_0000);
+    mmc1_serial_write(bus_cartridge(&cpu), 0xE000, 1);
+
+    // INC $F000
+    cpu.program_counter = 0x0000;
+    cpu.write(0x0000, 0xEE);
+    cpu.write16(0x0001, 0xF000);
+    cpu.run_next_instruction();
+
+    // The $FF reset it back to last bank fixed and the $00 straight after was ignored, 
+    //  so the half written bit is gone too
+    mmc1_write(bus_cartridge(&cpu), 0xE000, 2);
+    assert_eq!(cpu.bus.cartridge().unwrap().borrow().cpu_peek(0x8000), 2);
+    assert_eq!(cpu.bus.cartridge().unwrap().borrow().cpu_peek(0xC000), 7);
+}
+
+#[test]
+fn mmc1_read_modify_write_takes_one_bit() {
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(create_ines_image(8, 2, 0x10, 0x00)).unwrap()).unwrap();
+    let mut cpu = CPU::with_bus(bus);
+
+    // INC $8000 writes back the $00 that is there, then $01 on the next cycle
+    cpu.program_counter = 0x0000;
+    cpu.write(0x0000, 0xEE);
+    cpu.write16(0x0001, 0x8000);
+    cpu.run_next_instruction();
+
+    // Only the $00 went in, so four more bits finish the register
+    for bit in [1, 0, 0, 0] {
+        mmc1_serial_write(bus_cartridge(&cpu), 0xE000, bit);
+    }
+    assert_eq!(cpu.bus.cartridge().unwrap().borrow().cpu_peek(0x8000), 2);
+}
+
+fn bus_cartridge(cpu: &CPU<NESBus>) -> &Cartridge {
+    cpu.bus.cartridge().unwrap()
+}
+
+#[test]
+fn mmc1_program_ram_enable() {
+    let cartridge = create_cartridge(2, 2, 0x10);
+    cartridge.borrow_mut().cpu_write(0x6000, 0x12);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x12);
+
+    mmc1_write(&cartridge, 0xE000, 0b1_0000);
+    cartridge.borrow_mut().cpu_write(0x6000, 0x34);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x00);
+
+    mmc1_write(&cartridge, 0xE000, 0b0_0000);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x12);
+}
+
+#[test]
+fn uxrom_switches_bank_at_8000() {
+    let cartridge = create_cartridge(8, 0, 0x20);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 7);
+
+    // Written over the fixed bank, which is all 7s so nothing gets lost to the bus conflict
+    cartridge.borrow_mut().cpu_write(0xC000, 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0xBFFF), 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0xFFFF), 7);
+}
+
+#[test]
+fn uxrom_bus_conflict() {
+    let cartridge = create_cartridge(4, 0, 0x20);
+
+    // The ROM has 3 at that address, 6 & 3 is 2
+    cartridge.borrow_mut().cpu_write(0xC000, 6);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 2);
+}
+
+#[test]
+fn uxrom_without_bus_conflicts() {
+    let cartridge = create_cartridge_with_submapper(4, 0, 0x20, 1);
+
+    cartridge.borrow_mut().cpu_write(0x8000, 2);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 2);
+}
+
+#[test]
+fn uxrom_chr_ram() {
+    let cartridge = create_cartridge(2, 0, 0x20);
+    cartridge.borrow_mut().ppu_write(0x1FFF, 0x42);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x1FFF), 0x42);
+}
+
+#[test]
+fn cnrom_switches_chr_bank() {
+    let cartridge = create_cartridge(2, 4, 0x30);
+
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x80);
+
+    // The second PRG bank is all 1s, so write there to get past the bus conflict
+    cartridge.borrow_mut().cpu_write(0xC000, 1);
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x81);
+    assert_eq!(cartridge.borrow().ppu_read(0x1FFF), 0x81);
+}
+
+#[test]
+fn cnrom_bus_conflict() {
+    let cartridge = create_cartridge(2, 4, 0x30);
+
+    // The first PRG bank is all 0s
+    cartridge.borrow_mut().cpu_write(0x8000, 3);
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x80);
+}
+
+#[test]
+fn cnrom_without_bus_conflicts() {
+    let cartridge = create_cartridge_with_submapper(2, 4, 0x30, 1);
+
+    cartridge.borrow_mut().cpu_write(0x8000, 3);
+    assert_eq!(cartridge.borrow().ppu_read(0x0000), 0x83);
+}
+
+#[test]
+fn cnrom_program_rom_like_nrom() {
+    let cartridge = create_cartridge(1, 1, 0x30);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 0);
+}
+
+#[test]
+fn axrom_switches_32k_banks() {
+    let cartridge = create_cartridge(8, 0, 0x70);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 1);
+
+    cartridge.borrow_mut().cpu_write(0x8000, 2);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 4);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 5);
+}
+
+#[test]
+fn axrom_single_screen_mirroring() {
+    let cartridge = create_cartridge(8, 0, 0x70);
+    assert_eq!(cartridge.borrow().mirroring(), Mirroring::SingleScreenLower);
+
+    cartridge.borrow_mut().cpu_write(0x8000, 0x10);
+    assert_eq!(cartridge.borrow().mirroring(), Mirroring::SingleScreenUpper);
+}
+
+#[test]
+fn axrom_bus_conflict_only_on_anrom() {
+    let cartridge = create_cartridge_with_submapper(8, 0, 0x70, 2);
+
+    // Bank 0 is all 0s so the write gets eaten
+    cartridge.borrow_mut().cpu_write(0x8000, 0x12);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 0);
+    assert_eq!(cartridge.borrow().mirroring(), Mirroring::SingleScreenLower);
+}
+
+// 128KB of PRG and CHR with the bank number written at the start of every 8KB PRG bank 
+//  and every 1KB CHR bank
+fn create_mmc3_cartridge() -> Cartridge {
+    let mut image = create_ines_image(8, 16, 0x40, 0x00);
+    for bank in 0..16 {
+        image[16 + bank * 0x2000] = bank as u8;
+    }
+    for bank in 0..128 {
+        image[16 + 8 * 0x4000 + bank * 0x0400] = bank as u8;
+    }
+
+    create_mapper(&INES::from_bytes(image).unwrap()).unwrap()
+}
+
+fn mmc3_set_bank(cartridge: &Cartridge, bank_select: u8, bank: u8) {
+    cartridge.borrow_mut().cpu_write(0x8000, bank_select);
+    cartridge.borrow_mut().cpu_write(0x8001, bank);
+}
+
+// What the PPU does to A12 on a normal scanline, background from $0000 then sprites from $1000
+fn mmc3_scanline(cartridge: &Cartridge, scanline: u64) {
+    let start = scanline * 341;
+    cartridge.borrow_mut().ppu_address(0x0000, start);
+    cartridge.borrow_mut().ppu_address(0x1000, start + 257);
+}
+
+#[test]
+fn mmc3_program_banks() {
+    let cartridge = create_mmc3_cartridge();
+    mmc3_set_bank(&cartridge, 6, 3);
+    mmc3_set_bank(&cartridge, 7, 5);
+
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 3);
+    assert_eq!(cartridge.borrow().cpu_peek(0xA000), 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 14);
+    assert_eq!(cartridge.borrow().cpu_peek(0xE000), 15);
+
+    // Swapped mode puts the second last bank at $8000 and R6 at $C000
+    mmc3_set_bank(&cartridge, 0x46, 3);
+    assert_eq!(cartridge.borrow().cpu_peek(0x8000), 14);
+    assert_eq!(cartridge.borrow().cpu_peek(0xA000), 5);
+    assert_eq!(cartridge.borrow().cpu_peek(0xC000), 3);
+    assert_eq!(cartridge.borrow().cpu_peek(0xE000), 15);
+}
+
+#[test]
+fn mmc3_chr_banks() {
+    let cartridge = create_mmc3_cartridge();
+    for (register, bank) in [9, 20, 30, 31, 32, 33].iter().enumerate() {
+        mmc3_set_bank(&cartridge, register as u8, *bank);
+    }
+
+    // The 2KB banks ignore the low bit
+    let expected = [8, 9, 20, 21, 30, 31, 32, 33];
+    for (slot, bank) in expected.iter().enumerate() {
+        assert_eq!(cartridge.borrow().ppu_read(slot as u16 * 0x0400), *bank);
+    }
+
+    // Inverted swaps the halves over
+    cartridge.borrow_mut().cpu_write(0x8000, 0x80);
+    let expected = [30, 31, 32, 33, 8, 9, 20, 21];
+    for (slot, bank) in expected.iter().enumerate() {
+        assert_eq!(cartridge.borrow().ppu_read(slot as u16 * 0x0400), *bank);
+    }
+}
+
+#[test]
+fn mmc3_mirroring() {
+    let cartridge = create_mmc3_cartridge();
+
+    cartridge.borrow_mut().cpu_write(0xA000, 1);
+    assert_eq!(cartridge.borrow().mirroring(), Mirroring::Horizontal);
+    cartridge.borrow_mut().cpu_write(0xA000, 0);
+    assert_eq!(cartridge.borrow().mirroring(), Mirroring::Vertical);
+}
+
+#[test]
+fn mmc3_program_ram_protect() {
+    let cartridge = create_mmc3_cartridge();
+    cartridge.borrow_mut().cpu_write(0xA001, 0x80);
+    cartridge.borrow_mut().cpu_write(0x6000, 0x12);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x12);
+
+    // Write protected
+    cartridge.borrow_mut().cpu_write(0xA001, 0xC0);
+    cartridge.borrow_mut().cpu_write(0x6000, 0x34);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x12);
+
+    // Disabled
+    cartridge.borrow_mut().cpu_write(0xA001, 0x00);
+    assert_eq!(cartridge.borrow().cpu_peek(0x6000), 0x00);
+}
+
+#[test]
+fn mmc3_irq_after_latch_scanlines() {
+    let cartridge = create_mmc3_cartridge();
+    cartridge.borrow_mut().cpu_write(0xC000, 3);
+    cartridge.borrow_mut().cpu_write(0xC001, 0);
+    cartridge.borrow_mut().cpu_write(0xE001, 0);
+
+    // The first clock reloads the counter, then it counts down 3, 2, 1, 0
+    for scanline in 0..3 {
+        mmc3_scanline(&cartridge, scanline);
+        assert!(!cartridge.borrow().irq());
+    }
+    mmc3_scanline(&cartridge, 3);
+    assert!(cartridge.borrow().irq());
+
+    // Stays asserted until acknowledged
+    mmc3_scanline(&cartridge, 4);
+    assert!(cartridge.borrow().irq());
+    cartridge.borrow_mut().cpu_write(0xE000, 0);
+    assert!(!cartridge.borrow().irq());
+}
+
+#[test]
+fn mmc3_irq_disabled() {
+    let cartridge = create_mmc3_cartridge();
+    cartridge.borrow_mut().cpu_write(0xC000, 0);
+    cartridge.borrow_mut().cpu_write(0xC001, 0);
+
+    for scanline in 0..4 {
+        mmc3_scanline(&cartridge, scanline);
+    }
+    assert!(!cartridge.borrow().irq());
+}
+
+#[test]
+fn mmc3_a12_filter() {
+    let cartridge = create_mmc3_cartridge();
+    cartridge.borrow_mut().cpu_write(0xC000, 1);
+    cartridge.borrow_mut().cpu_write(0xC001, 0);
+    cartridge.borrow_mut().cpu_write(0xE001, 0);
+
+    // Reload, then 8x16 sprites bouncing A12 up and down a few dots apart only count once
+    mmc3_scanline(&cartridge, 0);
+    for dot in 0..8 {
+        cartridge.borrow_mut().ppu_address(0x0000, 341 + 257 + dot * 8);
+        cartridge.borrow_mut().ppu_address(0x1000, 341 + 261 + dot * 8);
+    }
+    assert!(!cartridge.borrow().irq());
+
+    mmc3_scanline(&cartridge, 2);
+    assert!(cartridge.borrow().irq());
+}
+
+#[test]
+fn mmc3_irq_from_rendering_reaches_cpu() {
+    let mut image = create_ines_image(2, 1, 0x40, 0x00);
+    let program: Vec<u8> = vec![
+        0xA9, 0x08, 0x8D, 0x00, 0x20,   // LDA #$08, STA $2000 (sprites from $1000)
+        0xA9, 0x18, 0x8D, 0x01, 0x20,   // LDA #$18, STA $2001
+        0xA9, 0x05, 0x8D, 0x00, 0xC0,   // LDA #$05, STA $C000
+        0x8D, 0x01, 0xC0,               // STA $C001
+        0x8D, 0x01, 0xE0,               // STA $E001
+        0x58,                           // CLI
+        0x4C, 0x16, 0xE0,               // JMP $E016
+    ];
+    let handler: Vec<u8> = vec![
+        0xE6, 0x00,                     // INC $00
+        0x4C, 0x02, 0xE1                // JMP $E102
+    ];
+    // $E000 - $FFFF is the last 8KB of the second 16KB bank
+    let last_bank = 16 + 0x4000 + 0x2000;
+    image[last_bank..last_bank + program.len()].copy_from_slice(&program);
+    image[last_bank + 0x100..last_bank + 0x100 + handler.len()].copy_from_slice(&handler);
+    image[last_bank + 0x1FFC..last_bank + 0x2000].copy_from_slice(&[0x00, 0xE0, 0x00, 0xE1]);
+
+    let mut bus = NESBus::new();
+    bus.load_rom(&INES::from_bytes(image).unwrap()).unwrap();
+    let mut cpu = CPU::with_bus(bus);
+
+    for _ in 0..2000 {
+        cpu.run_next_instruction();
+        if cpu.bus.peek(0x0000) != 0 {
+            break;
+        }
+    }
+
+    // Rendering was turned on before the end of scanline 0, which reloaded the counter with 5
+    assert_eq!(cpu.bus.peek(0x0000), 1);
+    assert_eq!(cpu.bus.ppu.scanline, 5);
+}
@@ -0,0 +1,110 @@
+use crate::bus::Bus;
+use crate::file_format::INES;
+use crate::nes::NES;
+use crate::ppu::*;
+use crate::tests::test_util::create_ines_image;
+
+// NROM-128 that turns the background on with a blue backdrop and then spins
+fn create_rom() -> INES {
+    let mut image = create_ines_image(1, 0, 0x00, 0x00);
+    let program: Vec<u8> = vec![
+        0xA9, 0x3F, 0x8D, 0x06, 0x20,   // LDA #$3F, STA $2006
+        0xA9, 0x00, 0x8D, 0x06, 0x20,   // LDA #$00, STA $2006
+        0xA9, 0x12, 0x8D, 0x07, 0x20,   // LDA #$12, STA $2007
+        0xA9, 0x0A, 0x8D, 0x01, 0x20,   // LDA #$0A, STA $2001
+        0x4C, 0x14, 0x80                // JMP $8014
+    ];
+    image[16..16 + program.len()].copy_from_slice(&program);
+    // Reset vector, $FFFC in a mirrored 16KB bank
+    image[16 + 0x3FFC] = 0x00;
+    image[16 + 0x3FFD] = 0x80;
+
+    INES::from_bytes(image).unwrap()
+}
+
+#[test]
+fn starts_at_reset_vector() {
+    let nes = NES::from_rom(&create_rom()).unwrap();
+
+    assert_eq!(nes.cpu.program_counter, 0x8000);
+}
+
+#[test]
+fn run_frame_finishes_one_frame() {
+    let mut nes = NES::from_rom(&create_rom()).unwrap();
+
+    nes.run_frame();
+    nes.run_frame();
+
+    assert_eq!(nes.cpu.bus.ppu.frame_count, 2);
+    assert_eq!(nes.frame().len(), FRAME_WIDTH * FRAME_HEIGHT);
+    assert!(nes.frame().iter().all(|colour| *colour == 0x12));
+}
+
+fn temp_save_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("nes_emulator_nes_test_{}", name))
+}
+
+#[test]
+fn battery_flag_comes_from_header() {
+    assert!(!NES::from_rom(&create_rom()).unwrap().has_battery());
+    assert!(NES::from_rom(&INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap()).unwrap().has_battery());
+}
+
+#[test]
+fn save_round_trip() {
+    let path = temp_save_path("round_trip.sav");
+    let rom = INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap();
+
+    let mut nes = NES::from_rom(&rom).unwrap();
+    nes.cpu.bus.write(0x6000, 0x12);
+    nes.cpu.bus.write(0x7FFF, 0x34);
+    nes.write_save(&path).unwrap();
+
+    let mut nes = NES::from_rom(&rom).unwrap();
+    nes.load_save(&path).unwrap();
+    let saved_length = std::fs::metadata(&path).unwrap().len();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(saved_length, 0x2000);
+    assert_eq!(nes.cpu.bus.read(0x6000), 0x12);
+    assert_eq!(nes.cpu.bus.read(0x7FFF), 0x34);
+}
+
+#[test]
+fn only_program_ram_writes_need_saving() {
+    let path = temp_save_path("unsaved_changes.sav");
+    let mut nes = NES::from_rom(&INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap()).unwrap();
+
+    nes.cpu.bus.write(0x0000, 0x12);
+    nes.cpu.bus.write(0x8000, 0x12);
+    assert!(!nes.has_unsaved_changes());
+
+    nes.cpu.bus.write(0x6000, 0x12);
+    assert!(nes.has_unsaved_changes());
+
+    nes.write_save(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(!nes.has_unsaved_changes());
+}
+
+#[test]
+fn missing_save_is_not_an_error() {
+    let mut nes = NES::from_rom(&INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap()).unwrap();
+
+    assert!(nes.load_save(&temp_save_path("does_not_exist.sav")).is_ok());
+    assert_eq!(nes.cpu.bus.read(0x6000), 0x00);
+}
+
+#[test]
+fn short_save_fills_the_start() {
+    let path = temp_save_path("short.sav");
+    std::fs::write(&path, [0xAB; 16]).unwrap();
+
+    let mut nes = NES::from_rom(&INES::from_bytes(create_ines_image(1, 0, 0x02, 0x00)).unwrap()).unwrap();
+    nes.load_save(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(nes.cpu.bus.read(0x600F), 0xAB);
+    assert_eq!(nes.cpu.bus.read(0x6010), 0x00);
+}
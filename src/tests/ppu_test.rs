@@ -0,0 +1,673 @@
+use crate::bus::*;
+use crate::file_format::*;
+use crate::mappers::mapper::create_mapper;
+use crate::ppu::*;
+use crate::tests::test_util::create_ines_image;
+
+fn set_vram_address(ppu: &mut PPU, addr: u16) {
+    ppu.write_register(PPUADDR, (addr >> 8) as u8);
+    ppu.write_register(PPUADDR, addr as u8);
+}
+
+#[test]
+fn ppuaddr_takes_two_writes() {
+    let mut ppu = PPU::new();
+
+    ppu.write_register(PPUADDR, 0x21);
+    assert!(ppu.w);
+    assert_eq!(ppu.v, 0x0000);
+
+    ppu.write_register(PPUADDR, 0x08);
+    assert!(!ppu.w);
+    assert_eq!(ppu.v, 0x2108);
+}
+
+#[test]
+fn ppuaddr_high_byte_is_six_bits() {
+    let mut ppu = PPU::new();
+    set_vram_address(&mut ppu, 0xFF00);
+
+    assert_eq!(ppu.v, 0x3F00);
+}
+
+#[test]
+fn ppudata_write_increments_by_one() {
+    let mut ppu = PPU::new();
+    set_vram_address(&mut ppu, 0x2000);
+
+    ppu.write_register(PPUDATA, 0x11);
+    ppu.write_register(PPUDATA, 0x22);
+
+    assert_eq!(ppu.v, 0x2002);
+    assert_eq!(ppu.read(0x2000), 0x11);
+    assert_eq!(ppu.read(0x2001), 0x22);
+}
+
+#[test]
+fn ppudata_write_increments_by_32() {
+    let mut ppu = PPU::new();
+    ppu.write_register(PPUCTRL, CTRL_VRAM_INCREMENT);
+    set_vram_address(&mut ppu, 0x2000);
+
+    ppu.write_register(PPUDATA, 0x11);
+    ppu.write_register(PPUDATA, 0x22);
+
+    assert_eq!(ppu.v, 0x2040);
+    assert_eq!(ppu.read(0x2020), 0x22);
+}
+
+#[test]
+fn ppudata_read_is_buffered() {
+    let mut ppu = PPU::new();
+    ppu.write(0x2400, 0x42);
+    ppu.write(0x2401, 0x43);
+    set_vram_address(&mut ppu, 0x2400);
+
+    // The first read gives back whatever was in the buffer
+    assert_eq!(ppu.read_register(PPUDATA), 0x00);
+    assert_eq!(ppu.read_register(PPUDATA), 0x42);
+    assert_eq!(ppu.read_register(PPUDATA), 0x43);
+}
+
+#[test]
+fn palette_read_is_not_buffered() {
+    let mut ppu = PPU::new();
+    ppu.write(0x3F01, 0x2A);
+    ppu.write(0x2F01, 0x55);
+    set_vram_address(&mut ppu, 0x3F01);
+
+    assert_eq!(ppu.read_register(PPUDATA), 0x2A);
+
+    // The buffer picks up the nametable byte below the palette
+    set_vram_address(&mut ppu, 0x2000);
+    assert_eq!(ppu.read_register(PPUDATA), 0x55);
+}
+
+#[test]
+fn status_read_clears_vblank_and_toggle() {
+    let mut ppu = PPU::new();
+    ppu.status = STATUS_VBLANK | STATUS_SPRITE_ZERO_HIT;
+    ppu.write_register(PPUSCROLL, 0x10);
+    assert!(ppu.w);
+
+    assert_eq!(ppu.read_register(PPUSTATUS) & 0xE0, STATUS_VBLANK | STATUS_SPRITE_ZERO_HIT);
+    assert!(!ppu.w);
+    assert_eq!(ppu.status, STATUS_SPRITE_ZERO_HIT);
+    assert_eq!(ppu.read_register(PPUSTATUS) & STATUS_VBLANK, 0);
+}
+
+#[test]
+fn status_low_bits_are_open_bus() {
+    let mut ppu = PPU::new();
+    ppu.write_register(PPUMASK, 0x1F);
+
+    assert_eq!(ppu.read_register(PPUSTATUS), 0x1F);
+}
+
+#[test]
+fn write_only_registers_read_open_bus() {
+    let mut ppu = PPU::new();
+    ppu.write_register(PPUCTRL, 0x80);
+
+    assert_eq!(ppu.read_register(PPUCTRL), 0x80);
+    assert_eq!(ppu.read_register(PPUSCROLL), 0x80);
+}
+
+#[test]
+fn ppuscroll_loads_t_and_fine_x() {
+    let mut ppu = PPU::new();
+    ppu.write_register(PPUCTRL, 0x03);
+    // X = 125, Y = 94
+    ppu.write_register(PPUSCROLL, 0x7D);
+    ppu.write_register(PPUSCROLL, 0x5E);
+
+    assert_eq!(ppu.x, 0x05);
+    // Fine Y 110, nametable 11, coarse Y 01011, coarse X 01111
+    assert_eq!(ppu.t, 0b0110_1101_0110_1111);
+}
+
+#[test]
+fn oam_data() {
+    let mut ppu = PPU::new();
+    ppu.write_register(OAMADDR, 0x10);
+    ppu.write_register(OAMDATA, 0x01);
+    ppu.write_register(OAMDATA, 0x02);
+
+    assert_eq!(ppu.oam[0x10], 0x01);
+    assert_eq!(ppu.oam[0x11], 0x02);
+    assert_eq!(ppu.oam_addr, 0x12);
+
+    // Reading doesn't move the address
+    ppu.write_register(OAMADDR, 0x10);
+    assert_eq!(ppu.read_register(OAMDATA), 0x01);
+    assert_eq!(ppu.read_register(OAMDATA), 0x01);
+}
+
+#[test]
+fn oam_attribute_unused_bits_read_as_zero() {
+    let mut ppu = PPU::new();
+    ppu.write_register(OAMADDR, 0x02);
+    ppu.write_register(OAMDATA, 0xFF);
+    ppu.write_register(OAMADDR, 0x02);
+
+    assert_eq!(ppu.read_register(OAMDATA), 0xE3);
+}
+
+#[test]
+fn horizontal_mirroring() {
+    let mut ppu = PPU::new();
+    ppu.mirroring = Mirroring::Horizontal;
+
+    ppu.write(0x2000, 0x11);
+    ppu.write(0x2800, 0x22);
+
+    assert_eq!(ppu.read(0x2400), 0x11);
+    assert_eq!(ppu.read(0x2C00), 0x22);
+}
+
+#[test]
+fn vertical_mirroring() {
+    let mut ppu = PPU::new();
+    ppu.mirroring = Mirroring::Vertical;
+
+    ppu.write(0x2000, 0x11);
+    ppu.write(0x2400, 0x22);
+
+    assert_eq!(ppu.read(0x2800), 0x11);
+    assert_eq!(ppu.read(0x2C00), 0x22);
+}
+
+#[test]
+fn single_screen_mirroring() {
+    let mut ppu = PPU::new();
+    ppu.mirroring = Mirroring::SingleScreenUpper;
+
+    ppu.write(0x2000, 0x11);
+
+    assert_eq!(ppu.read(0x2C00), 0x11);
+    assert_eq!(ppu.vram[0x400], 0x11);
+}
+
+#[test]
+fn four_screen_mirroring() {
+    let mut ppu = PPU::new();
+    ppu.mirroring = Mirroring::FourScreen;
+
+    ppu.write(0x2000, 0x11);
+    ppu.write(0x2400, 0x22);
+    ppu.write(0x2800, 0x33);
+    ppu.write(0x2C00, 0x44);
+
+    assert_eq!(ppu.read(0x2000), 0x11);
+    assert_eq!(ppu.read(0x2400), 0x22);
+    assert_eq!(ppu.read(0x2800), 0x33);
+    assert_eq!(ppu.read(0x2C00), 0x44);
+    assert_eq!(ppu.four_screen_vram[0x400], 0x44);
+}
+
+#[test]
+fn nametables_mirror_above_0x3000() {
+    let mut ppu = PPU::new();
+    ppu.write(0x2123, 0x42);
+
+    assert_eq!(ppu.read(0x3123), 0x42);
+}
+
+#[test]
+fn palette_mirrors() {
+    let mut ppu = PPU::new();
+    ppu.write(0x3F10, 0x0F);
+    ppu.write(0x3F05, 0x16);
+
+    assert_eq!(ppu.read(0x3F00), 0x0F);
+    assert_eq!(ppu.read(0x3F25), 0x16);
+    assert_eq!(ppu.read(0x3FE5), 0x16);
+}
+
+#[test]
+fn pattern_tables_read_chr() {
+    let mut image = create_ines_image(1, 1, 0x00, 0x00);
+    image[HEADER_SIZE + PROGRAM_ROM_BANK_SIZE + 1] = 0xBB;
+
+    let mut ppu = PPU::new();
+    ppu.cartridge = Some(create_mapper(&INES::from_bytes(image).unwrap()).unwrap());
+
+    assert_eq!(ppu.read(0x0000), 0x80);
+    assert_eq!(ppu.read(0x0001), 0xBB);
+}
+
+#[test]
+fn cpu_can_reach_vram_through_the_bus() {
+    let mut bus = NESBus::new();
+    bus.write(0x2006, 0x3F);
+    bus.write(0x2006, 0x00);
+    bus.write(0x2007, 0x21);
+
+    assert_eq!(bus.ppu.read(0x3F00), 0x21);
+}
+
+#[test]
+fn peek_does_not_clear_vblank() {
+    let mut bus = NESBus::new();
+    bus.ppu.status = STATUS_VBLANK;
+
+    assert_eq!(bus.peek(0x2002) & STATUS_VBLANK, STATUS_VBLANK);
+    assert_eq!(bus.read(0x2002) & STATUS_VBLANK, STATUS_VBLANK);
+    assert_eq!(bus.read(0x2002) & STATUS_VBLANK, 0);
+}
+
+// Runs until the start of the next frame
+fn run_frame(ppu: &mut PPU) {
+    ppu.frame_complete = false;
+    while !ppu.frame_complete {
+        ppu.tick();
+    }
+}
+
+// Tile 1 is solid colour 1, and it's the first tile of the first nametable
+fn create_ppu_with_tile() -> PPU {
+    let mut ppu = PPU::new();
+    for row in 0..8 {
+        ppu.chr[16 + row] = 0xFF;
+    }
+    ppu.write(0x2000, 0x01);
+    ppu.write(0x3F00, 0x0F);
+    ppu.write(0x3F01, 0x21);
+    ppu.write(0x3F05, 0x16);
+    ppu.mask = MASK_SHOW_BACKGROUND | MASK_SHOW_BACKGROUND_LEFT;
+    ppu
+}
+
+#[test]
+fn vblank_starts_on_scanline_241() {
+    let mut ppu = PPU::new();
+    ppu.write_register(PPUCTRL, CTRL_NMI_ENABLE);
+
+    while !(ppu.scanline == VBLANK_SCANLINE && ppu.dot == 1) {
+        ppu.tick();
+    }
+    assert_eq!(ppu.status & STATUS_VBLANK, 0);
+    assert!(!ppu.poll_nmi());
+
+    ppu.tick();
+    assert_eq!(ppu.status & STATUS_VBLANK, STATUS_VBLANK);
+    assert!(ppu.poll_nmi());
+    assert!(!ppu.poll_nmi());
+}
+
+#[test]
+fn vblank_clears_on_pre_render_line() {
+    let mut ppu = PPU::new();
+    ppu.status = STATUS_VBLANK | STATUS_SPRITE_ZERO_HIT;
+    ppu.scanline = PRE_RENDER_SCANLINE;
+    ppu.dot = 1;
+
+    ppu.tick();
+
+    assert_eq!(ppu.status, 0);
+}
+
+#[test]
+fn no_nmi_when_disabled() {
+    let mut ppu = PPU::new();
+
+    run_frame(&mut ppu);
+
+    assert!(!ppu.poll_nmi());
+}
+
+#[test]
+fn enabling_nmi_during_vblank_fires_one() {
+    let mut ppu = PPU::new();
+    ppu.status = STATUS_VBLANK;
+
+    ppu.write_register(PPUCTRL, CTRL_NMI_ENABLE);
+
+    assert!(ppu.poll_nmi());
+}
+
+#[test]
+fn frame_is_341_by_262_dots() {
+    let mut ppu = PPU::new();
+    let mut dots = 0;
+    ppu.frame_complete = false;
+    while !ppu.frame_complete {
+        ppu.tick();
+        dots += 1;
+    }
+
+    assert_eq!(dots, 341 * 262);
+    assert_eq!(ppu.frame_count, 1);
+}
+
+#[test]
+fn odd_frames_skip_a_dot_when_rendering() {
+    let mut ppu = PPU::new();
+    ppu.mask = MASK_SHOW_BACKGROUND;
+    run_frame(&mut ppu);
+
+    let mut dots = 0;
+    ppu.frame_complete = false;
+    while !ppu.frame_complete {
+        ppu.tick();
+        dots += 1;
+    }
+
+    assert_eq!(dots, 341 * 262 - 1);
+}
+
+#[test]
+fn background_tile() {
+    let mut ppu = create_ppu_with_tile();
+
+    // The first frame primes the pre-render line, the second one is drawn properly
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(&ppu.frame[0..8], &[0x21; 8]);
+    assert_eq!(ppu.frame[8], 0x0F);
+    // Every row of the tile
+    assert_eq!(ppu.frame[7 * FRAME_WIDTH], 0x21);
+    assert_eq!(ppu.frame[8 * FRAME_WIDTH], 0x0F);
+}
+
+#[test]
+fn background_attribute_selects_palette() {
+    let mut ppu = create_ppu_with_tile();
+    ppu.write(0x23C0, 0x01);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(ppu.frame[0], 0x16);
+}
+
+#[test]
+fn fine_x_scroll() {
+    let mut ppu = create_ppu_with_tile();
+    ppu.write_register(PPUSCROLL, 4);
+    ppu.write_register(PPUSCROLL, 0);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(&ppu.frame[0..4], &[0x21; 4]);
+    assert_eq!(ppu.frame[4], 0x0F);
+}
+
+#[test]
+fn coarse_scroll_moves_into_next_nametable() {
+    let mut ppu = create_ppu_with_tile();
+    ppu.mirroring = Mirroring::Vertical;
+    ppu.write(0x2000, 0x00);
+    ppu.write(0x2400, 0x01);
+    // Scrolled all the way right, so the screen starts on the second nametable
+    ppu.write_register(PPUCTRL, 0x01);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(ppu.frame[0], 0x21);
+    assert_eq!(ppu.frame[8], 0x0F);
+}
+
+#[test]
+fn left_column_can_be_hidden() {
+    let mut ppu = create_ppu_with_tile();
+    ppu.mask = MASK_SHOW_BACKGROUND;
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(&ppu.frame[0..8], &[0x0F; 8]);
+}
+
+#[test]
+fn greyscale() {
+    let mut ppu = create_ppu_with_tile();
+    ppu.mask |= MASK_GREYSCALE;
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(ppu.frame[0], 0x20);
+}
+
+#[test]
+fn cpu_gets_nmi_at_vblank() {
+    use crate::cpu::*;
+
+    let mut bus = NESBus::new();
+    // LDA #$80, STA $2000, loop: JMP loop
+    bus.load_program(0x8000, &[0xA9, 0x80, 0x8D, 0x00, 0x20, 0x4C, 0x05, 0x80]);
+    // INX, RTI
+    bus.load_program(0x9000, &[0xE8, 0x40]);
+    bus.write(NMI_VECTOR, 0x00);
+    bus.write(NMI_VECTOR + 1, 0x90);
+    bus.write(PROGRAM_READ_START, 0x00);
+    bus.write(PROGRAM_READ_START + 1, 0x80);
+
+    let mut cpu = CPU::with_bus(bus);
+
+    // A little over two frames
+    cpu.run_for_cycles(29781 * 2 + 100);
+
+    assert_eq!(cpu.registers.x, 2);
+    assert_eq!(cpu.bus.ppu_position().unwrap().0, cpu.bus.ppu.scanline);
+}
+
+// Tile 2 is a sprite that is colour 2 on the left half and clear on the right
+fn create_ppu_with_sprite(y: u8, x: u8, attributes: u8) -> PPU {
+    let mut ppu = create_ppu_with_tile();
+    for row in 0..8 {
+        ppu.chr[32 + 8 + row] = 0xF0;
+    }
+    // Move the background tile out of the way
+    ppu.write(0x2000, 0x00);
+    ppu.write(0x3F11, 0x05);
+    ppu.write(0x3F12, 0x2A);
+    ppu.write(0x3F16, 0x30);
+    ppu.oam[0..4].copy_from_slice(&[y, 0x02, attributes, x]);
+    // Everything else goes off screen
+    for sprite in 1..64 {
+        ppu.oam[sprite * 4] = 0xFF;
+    }
+    ppu.mask = MASK_SHOW_BACKGROUND | MASK_SHOW_BACKGROUND_LEFT | MASK_SHOW_SPRITES | MASK_SHOW_SPRITES_LEFT;
+    ppu
+}
+
+fn pixel(ppu: &PPU, x: usize, y: usize) -> u8 {
+    ppu.frame[y * FRAME_WIDTH + x]
+}
+
+#[test]
+fn sprite_is_drawn_one_line_below_its_y() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x00);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 20, 10), 0x0F);
+    assert_eq!(pixel(&ppu, 20, 11), 0x2A);
+    assert_eq!(pixel(&ppu, 23, 18), 0x2A);
+    assert_eq!(pixel(&ppu, 24, 11), 0x0F);
+    assert_eq!(pixel(&ppu, 20, 19), 0x0F);
+}
+
+#[test]
+fn sprite_palette() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x01);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 20, 11), 0x30);
+}
+
+#[test]
+fn sprite_horizontal_flip() {
+    let mut ppu = create_ppu_with_sprite(10, 20, SPRITE_FLIP_HORIZONTAL);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 20, 11), 0x0F);
+    assert_eq!(pixel(&ppu, 24, 11), 0x2A);
+    assert_eq!(pixel(&ppu, 27, 11), 0x2A);
+}
+
+#[test]
+fn sprite_vertical_flip() {
+    let mut ppu = create_ppu_with_sprite(10, 20, SPRITE_FLIP_VERTICAL);
+    // Only the top row of the tile is solid
+    for row in 1..8 {
+        ppu.chr[32 + 8 + row] = 0x00;
+    }
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 20, 11), 0x0F);
+    assert_eq!(pixel(&ppu, 20, 18), 0x2A);
+}
+
+#[test]
+fn sprite_behind_background() {
+    let mut ppu = create_ppu_with_sprite(0, 0, SPRITE_BEHIND_BACKGROUND);
+    ppu.write(0x2000, 0x01);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    // The background tile covers the top left, the sprite only shows where it's clear
+    assert_eq!(pixel(&ppu, 0, 1), 0x21);
+    ppu.write(0x2000, 0x00);
+    run_frame(&mut ppu);
+    assert_eq!(pixel(&ppu, 0, 1), 0x2A);
+}
+
+#[test]
+fn sprite_in_front_of_background() {
+    let mut ppu = create_ppu_with_sprite(0, 0, 0x00);
+    ppu.write(0x2000, 0x01);
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 0, 1), 0x2A);
+    assert_eq!(pixel(&ppu, 4, 1), 0x21);
+}
+
+#[test]
+fn tall_sprites() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x00);
+    ppu.ctrl = CTRL_SPRITE_SIZE;
+    // Tile 3 is the bottom half, solid colour 1
+    for row in 0..8 {
+        ppu.chr[48 + row] = 0xFF;
+    }
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 20, 11), 0x2A);
+    assert_eq!(pixel(&ppu, 27, 19), 0x05);
+    assert_eq!(pixel(&ppu, 27, 26), 0x05);
+    assert_eq!(pixel(&ppu, 27, 27), 0x0F);
+}
+
+#[test]
+fn sprites_can_be_hidden_in_left_column() {
+    let mut ppu = create_ppu_with_sprite(10, 0, 0x00);
+    ppu.mask &= !MASK_SHOW_SPRITES_LEFT;
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 0, 11), 0x0F);
+}
+
+#[test]
+fn only_eight_sprites_per_line() {
+    let mut ppu = create_ppu_with_sprite(10, 0, 0x00);
+    for sprite in 0..9 {
+        ppu.oam[sprite * 4..sprite * 4 + 4].copy_from_slice(&[10, 0x02, 0x00, (sprite * 16) as u8]);
+    }
+
+    run_frame(&mut ppu);
+    run_frame(&mut ppu);
+
+    assert_eq!(pixel(&ppu, 7 * 16, 11), 0x2A);
+    assert_eq!(pixel(&ppu, 8 * 16, 11), 0x0F);
+    assert_eq!(ppu.status & STATUS_SPRITE_OVERFLOW, 0);
+
+    // The flag is cleared on the pre-render line, so check it before then
+    while ppu.scanline != 100 {
+        ppu.tick();
+    }
+    assert_eq!(ppu.status & STATUS_SPRITE_OVERFLOW, STATUS_SPRITE_OVERFLOW);
+}
+
+#[test]
+fn sprite_overflow_bug_checks_the_wrong_byte() {
+    let mut ppu = create_ppu_with_sprite(10, 0, 0x00);
+    for sprite in 0..8 {
+        ppu.oam[sprite * 4..sprite * 4 + 4].copy_from_slice(&[10, 0x02, 0x00, 0x00]);
+    }
+    // The 9th sprite isn't on the line, so the hardware moves on to the next sprite but
+    //  also to the next byte. The 10th sprite is on the line, but its tile number gets
+    //  read as the Y position and it's missed.
+    ppu.oam[32..36].copy_from_slice(&[0xFF, 0x02, 0x00, 0x00]);
+    ppu.oam[36..40].copy_from_slice(&[10, 0xFF, 0x00, 0x00]);
+
+    while ppu.scanline != 100 {
+        ppu.tick();
+    }
+
+    assert_eq!(ppu.status & STATUS_SPRITE_OVERFLOW, 0);
+}
+
+#[test]
+fn sprite_zero_hit() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x00);
+    // Background tile under the sprite
+    ppu.write(0x2022, 0x01);
+    run_frame(&mut ppu);
+
+    // Row 11, and the sprite starts at x = 20 which is drawn on dot 21
+    while !(ppu.scanline == 11 && ppu.dot == 21) {
+        ppu.tick();
+        assert_eq!(ppu.status & STATUS_SPRITE_ZERO_HIT, 0);
+    }
+    ppu.tick();
+
+    assert_eq!(ppu.status & STATUS_SPRITE_ZERO_HIT, STATUS_SPRITE_ZERO_HIT);
+}
+
+#[test]
+fn no_sprite_zero_hit_on_transparent_background() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x00);
+
+    run_frame(&mut ppu);
+    while ppu.scanline != 100 {
+        ppu.tick();
+    }
+
+    assert_eq!(ppu.status & STATUS_SPRITE_ZERO_HIT, 0);
+}
+
+#[test]
+fn only_sprite_zero_hits() {
+    let mut ppu = create_ppu_with_sprite(10, 20, 0x00);
+    ppu.write(0x2022, 0x01);
+    ppu.oam.swap(0, 4);
+    ppu.oam[4..8].copy_from_slice(&[10, 0x02, 0x00, 20]);
+    ppu.oam[0] = 0xFF;
+
+    run_frame(&mut ppu);
+    while ppu.scanline != 100 {
+        ppu.tick();
+    }
+
+    assert_eq!(ppu.status & STATUS_SPRITE_ZERO_HIT, 0);
+}
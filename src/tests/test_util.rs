@@ -60,4 +60,22 @@ pub fn split_address_in_two(val: u16) -> (u16, u16) {
     let b = val - a;
     
     (a, b)
-}
\ No newline at end of file
+}
+
+// Builds an iNES image. Every byte of a PRG bank is the bank number, CHR banks are the 
+//  bank number plus 0x80 so the two can be told apart.
+pub fn create_ines_image(program_banks: u8, char_banks: u8, flags_6: u8, flags_7: u8) -> Vec<u8> {
+    let mut image: Vec<u8> = vec![b'N', b'E', b'S', 0x1A, program_banks, char_banks, flags_6, flags_7, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    if flags_6 & 0x04 != 0 {
+        image.extend(vec![0xEE; 512]);
+    }
+    for bank in 0..program_banks {
+        image.extend(vec![bank; 16384]);
+    }
+    for bank in 0..char_banks {
+        image.extend(vec![bank + 0x80; 8192]);
+    }
+
+    image
+}
@@ -0,0 +1,157 @@
+use crate::cpu::*;
+use crate::trace::*;
+
+// Sets the CPU up the way nestest.log expects it, at $C000 straight out of reset
+fn create_nestest_cpu(program: &[u8]) -> CPU {
+    let mut cpu = CPU::new(vec![]);
+    for (offset, byte) in program.iter().enumerate() {
+        cpu.write(0xC000 + offset as u16, *byte);
+    }
+
+    cpu.program_counter = 0xC000;
+    cpu.stack_pointer = 0xFD;
+    cpu.status = INTERRUPT_DISABLE;
+    cpu.cycles = 7;
+    cpu
+}
+
+#[test]
+fn jmp_absolute() {
+    let cpu = create_nestest_cpu(&[0x4C, 0xF5, 0xC5]);
+
+    assert_eq!(format_trace_line(&cpu),
+        "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7");
+}
+
+#[test]
+fn immediate() {
+    let mut cpu = create_nestest_cpu(&[0xA2, 0x00]);
+    cpu.cycles = 10;
+
+    assert_eq!(format_trace_line(&cpu),
+        "C000  A2 00     LDX #$00                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 30 CYC:10");
+}
+
+#[test]
+fn zero_page_shows_value() {
+    let mut cpu = create_nestest_cpu(&[0x86, 0x00]);
+    cpu.status = INTERRUPT_DISABLE | ZERO;
+    cpu.cycles = 12;
+
+    assert_eq!(format_trace_line(&cpu),
+        "C000  86 00     STX $00 = 00                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 36 CYC:12");
+}
+
+#[test]
+fn indirect_x() {
+    let mut cpu = create_nestest_cpu(&[0xA1, 0x80]);
+    cpu.write16(0x0080, 0x0200);
+    cpu.write(0x0200, 0x5A);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[16..48], "LDA ($80,X) @ 80 = 0200 = 5A    ");
+}
+
+#[test]
+fn indirect_y() {
+    let mut cpu = create_nestest_cpu(&[0xB1, 0x89]);
+    cpu.registers.y = 0x34;
+    cpu.write16(0x0089, 0x0300);
+    cpu.write(0x0334, 0x89);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[16..48], "LDA ($89),Y = 0300 @ 0334 = 89  ");
+}
+
+#[test]
+fn absolute_x() {
+    let mut cpu = create_nestest_cpu(&[0xBD, 0x00, 0x03]);
+    cpu.registers.x = 0x01;
+    cpu.write(0x0301, 0x77);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[16..48], "LDA $0300,X @ 0301 = 77         ");
+}
+
+#[test]
+fn jmp_indirect() {
+    let mut cpu = create_nestest_cpu(&[0x6C, 0x00, 0x02]);
+    cpu.write16(0x0200, 0xDB7E);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[16..48], "JMP ($0200) = DB7E              ");
+}
+
+#[test]
+fn branch_target_is_absolute() {
+    let cpu = create_nestest_cpu(&[0xB0, 0x04]);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[0..48], "C000  B0 04     BCS $C006                       ");
+}
+
+#[test]
+fn accumulator() {
+    let cpu = create_nestest_cpu(&[0x4A]);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[0..48], "C000  4A        LSR A                           ");
+}
+
+#[test]
+fn unofficial_instructions_are_starred() {
+    let cpu = create_nestest_cpu(&[0x04, 0xA9]);
+
+    let line = format_trace_line(&cpu);
+    assert_eq!(&line[0..48], "C000  04 A9    *NOP $A9 = 00                    ");
+}
+
+#[test]
+fn ppu_position_wraps_scanlines() {
+    let mut cpu = create_nestest_cpu(&[0xEA]);
+    // 341 dots is one scanline, so 114 cycles is 342 dots
+    cpu.cycles = 114;
+
+    let line = format_trace_line(&cpu);
+    assert!(line.ends_with("PPU:  1,  1 CYC:114"));
+}
+
+#[test]
+fn power_on_state_matches_nestest() {
+    use crate::bus::*;
+
+    let mut bus = FlatBus::new();
+    bus.write(PROGRAM_READ_START, 0x00);
+    bus.write(PROGRAM_READ_START + 1, 0xC0);
+    let cpu = CPU::with_bus(bus);
+
+    assert_eq!(cpu.program_counter, 0xC000);
+    assert_eq!(cpu.stack_pointer, 0xFD);
+    assert_eq!(cpu.status | UNUSED, 0x24);
+    assert_eq!(cpu.cycles, 7);
+}
+
+struct FailingWriter;
+
+impl std::io::Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_trace_write_stops_tracing() {
+    let mut cpu = create_nestest_cpu(&[0xE8, 0xE8]);
+    cpu.trace = Some(TraceLogger::new(Box::new(FailingWriter)));
+
+    cpu.run_next_instruction();
+    assert!(cpu.trace.is_none());
+
+    // The CPU itself keeps going
+    cpu.run_next_instruction();
+    assert_eq!(cpu.registers.x, 2);
+}
@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+
+use crate::addressing_modes::AddressingMode;
+use crate::bus::Bus;
+use crate::cpu::*;
+use crate::disassembler::instruction_length;
+use crate::util::*;
+
+pub const DOTS_PER_SCANLINE: u64 = 341;
+pub const SCANLINES_PER_FRAME: u64 = 262;
+
+// Writes one line per instruction in the same format Nintendulator uses, which is what
+//  the canonical nestest.log was made with. A line looks like:
+//
+//  C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+//
+// To line up with nestest.log the CPU needs to start at $C000 straight out of reset.
+pub struct TraceLogger {
+    out: Box<dyn Write>
+}
+
+impl TraceLogger {
+    pub fn new(out: Box<dyn Write>) -> Self {
+        Self {
+            out
+        }
+    }
+
+    pub fn to_file(path: &str) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::new(Box::new(BufWriter::new(file))))
+    }
+
+    pub fn log<B: Bus>(&mut self, cpu: &CPU<B>) -> std::io::Result<()> {
+        writeln!(self.out, "{}", format_trace_line(cpu))
+    }
+}
+
+impl Drop for TraceLogger {
+    fn drop(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+pub fn format_trace_line<B: Bus>(cpu: &CPU<B>) -> String {
+    let pc = cpu.program_counter;
+    let instruction = cpu.bus.peek(pc);
+    let mode = map_instruction_to_addressing_mode(instruction);
+    let length = instruction_length(mode);
+
+    let raw_bytes: Vec<String> = (0..length)
+        .map(|offset| format!("{:02X}", cpu.bus.peek(pc.wrapping_add(offset))))
+        .collect();
+
+    // Unofficial instructions get a star in front of the mnemonic
+    let marker = if is_unofficial_instruction(instruction) { "*" } else { " " };
+
+    let (scanline, dot) = match cpu.bus.ppu_position() {
+        Some(position) => position,
+        None => {
+            // With no PPU attached, work out where it would be. The PPU runs three dots
+            //  for every CPU cycle.
+            let dots = cpu.cycles * 3;
+            (((dots / DOTS_PER_SCANLINE) % SCANLINES_PER_FRAME) as u16, (dots % DOTS_PER_SCANLINE) as u16)
+        }
+    };
+
+    format!("{:04X}  {:<8} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        pc,
+        raw_bytes.join(" "),
+        marker,
+        format_trace_disassembly(cpu, instruction, mode),
+        cpu.registers.a,
+        cpu.registers.x,
+        cpu.registers.y,
+        cpu.status | UNUSED,
+        cpu.stack_pointer,
+        scanline,
+        dot,
+        cpu.cycles)
+}
+
+// The nestest flavour of disassembly also shows the effective address and what is in
+//  memory there, so this is separate from the regular disassembler.
+fn format_trace_disassembly<B: Bus>(cpu: &CPU<B>, instruction: u8, mode: AddressingMode) -> String {
+    let bus = &cpu.bus;
+    let pc = cpu.program_counter;
+    let name = map_instruction_to_name(instruction);
+
+    let byte = bus.peek(pc.wrapping_add(1));
+    let word = (bus.peek(pc.wrapping_add(2)) as u16) << 8 | byte as u16;
+
+    match mode {
+        AddressingMode::Implied => name.to_string(),
+        AddressingMode::Accumulator => format!("{} A", name),
+        AddressingMode::Immediate => format!("{} #${:02X}", name, byte),
+        AddressingMode::ZeroPage => format!("{} ${:02X} = {:02X}", name, byte, bus.peek(byte as u16)),
+        AddressingMode::ZeroPage_X => {
+            let addr = byte.wrapping_add(cpu.registers.x);
+            format!("{} ${:02X},X @ {:02X} = {:02X}", name, byte, addr, bus.peek(addr as u16))
+        },
+        AddressingMode::ZeroPage_Y => {
+            let addr = byte.wrapping_add(cpu.registers.y);
+            format!("{} ${:02X},Y @ {:02X} = {:02X}", name, byte, addr, bus.peek(addr as u16))
+        },
+        AddressingMode::Absolute => {
+            match instruction {
+                // Jumps don't read the address so there is nothing to show
+                0x4C | 0x20 => format!("{} ${:04X}", name, word),
+                _ => format!("{} ${:04X} = {:02X}", name, word, bus.peek(word))
+            }
+        },
+        AddressingMode::Absolute_X => {
+            let addr = word.wrapping_add(cpu.registers.x as u16);
+            format!("{} ${:04X},X @ {:04X} = {:02X}", name, word, addr, bus.peek(addr))
+        },
+        AddressingMode::Absolute_Y => {
+            let addr = word.wrapping_add(cpu.registers.y as u16);
+            format!("{} ${:04X},Y @ {:04X} = {:02X}", name, word, addr, bus.peek(addr))
+        },
+        AddressingMode::Indirect_X => {
+            let ptr = byte.wrapping_add(cpu.registers.x);
+            let addr = (bus.peek(ptr.wrapping_add(1) as u16) as u16) << 8 | bus.peek(ptr as u16) as u16;
+            format!("{} (${:02X},X) @ {:02X} = {:04X} = {:02X}", name, byte, ptr, addr, bus.peek(addr))
+        },
+        AddressingMode::Indirect_Y => {
+            let base = (bus.peek(byte.wrapping_add(1) as u16) as u16) << 8 | bus.peek(byte as u16) as u16;
+            let addr = base.wrapping_add(cpu.registers.y as u16);
+            format!("{} (${:02X}),Y = {:04X} @ {:04X} = {:02X}", name, byte, base, addr, bus.peek(addr))
+        },
+        AddressingMode::Indirect => {
+            // Same page wrapping bug as the real JMP
+            let low = bus.peek(word);
+            let high = bus.peek((word & 0xFF00) | (word.wrapping_add(1) & 0x00FF));
+            format!("{} (${:04X}) = {:04X}", name, word, (high as u16) << 8 | low as u16)
+        },
+        AddressingMode::Relative => {
+            let target = pc.wrapping_add(2).wrapping_add(byte as i8 as u16);
+            format!("{} ${:04X}", name, target)
+        }
+    }
+}
@@ -209,7 +209,134 @@ pub fn map_instruction_to_addressing_mode(instruction: u8) -> AddressingMode {
         0x9A => AddressingMode::Implied,
         // TYA
         0x98 => AddressingMode::Implied,
-        _ => panic!("Got unrecognized instruction while mapping addressing mode. ")
+        // ----- Unofficial instructions -----
+        // NOP
+        0x1A => AddressingMode::Implied,
+        0x3A => AddressingMode::Implied,
+        0x5A => AddressingMode::Implied,
+        0x7A => AddressingMode::Implied,
+        0xDA => AddressingMode::Implied,
+        0xFA => AddressingMode::Implied,
+        0x80 => AddressingMode::Immediate,
+        0x82 => AddressingMode::Immediate,
+        0x89 => AddressingMode::Immediate,
+        0xC2 => AddressingMode::Immediate,
+        0xE2 => AddressingMode::Immediate,
+        0x04 => AddressingMode::ZeroPage,
+        0x44 => AddressingMode::ZeroPage,
+        0x64 => AddressingMode::ZeroPage,
+        0x14 => AddressingMode::ZeroPage_X,
+        0x34 => AddressingMode::ZeroPage_X,
+        0x54 => AddressingMode::ZeroPage_X,
+        0x74 => AddressingMode::ZeroPage_X,
+        0xD4 => AddressingMode::ZeroPage_X,
+        0xF4 => AddressingMode::ZeroPage_X,
+        0x0C => AddressingMode::Absolute,
+        0x1C => AddressingMode::Absolute_X,
+        0x3C => AddressingMode::Absolute_X,
+        0x5C => AddressingMode::Absolute_X,
+        0x7C => AddressingMode::Absolute_X,
+        0xDC => AddressingMode::Absolute_X,
+        0xFC => AddressingMode::Absolute_X,
+        // LAX
+        0xA7 => AddressingMode::ZeroPage,
+        0xB7 => AddressingMode::ZeroPage_Y,
+        0xAF => AddressingMode::Absolute,
+        0xBF => AddressingMode::Absolute_Y,
+        0xA3 => AddressingMode::Indirect_X,
+        0xB3 => AddressingMode::Indirect_Y,
+        // SAX
+        0x87 => AddressingMode::ZeroPage,
+        0x97 => AddressingMode::ZeroPage_Y,
+        0x8F => AddressingMode::Absolute,
+        0x83 => AddressingMode::Indirect_X,
+        // SBC
+        0xEB => AddressingMode::Immediate,
+        // DCP
+        0xC7 => AddressingMode::ZeroPage,
+        0xD7 => AddressingMode::ZeroPage_X,
+        0xCF => AddressingMode::Absolute,
+        0xDF => AddressingMode::Absolute_X,
+        0xDB => AddressingMode::Absolute_Y,
+        0xC3 => AddressingMode::Indirect_X,
+        0xD3 => AddressingMode::Indirect_Y,
+        // ISB
+        0xE7 => AddressingMode::ZeroPage,
+        0xF7 => AddressingMode::ZeroPage_X,
+        0xEF => AddressingMode::Absolute,
+        0xFF => AddressingMode::Absolute_X,
+        0xFB => AddressingMode::Absolute_Y,
+        0xE3 => AddressingMode::Indirect_X,
+        0xF3 => AddressingMode::Indirect_Y,
+        // SLO
+        0x07 => AddressingMode::ZeroPage,
+        0x17 => AddressingMode::ZeroPage_X,
+        0x0F => AddressingMode::Absolute,
+        0x1F => AddressingMode::Absolute_X,
+        0x1B => AddressingMode::Absolute_Y,
+        0x03 => AddressingMode::Indirect_X,
+        0x13 => AddressingMode::Indirect_Y,
+        // RLA
+        0x27 => AddressingMode::ZeroPage,
+        0x37 => AddressingMode::ZeroPage_X,
+        0x2F => AddressingMode::Absolute,
+        0x3F => AddressingMode::Absolute_X,
+        0x3B => AddressingMode::Absolute_Y,
+        0x23 => AddressingMode::Indirect_X,
+        0x33 => AddressingMode::Indirect_Y,
+        // SRE
+        0x47 => AddressingMode::ZeroPage,
+        0x57 => AddressingMode::ZeroPage_X,
+        0x4F => AddressingMode::Absolute,
+        0x5F => AddressingMode::Absolute_X,
+        0x5B => AddressingMode::Absolute_Y,
+        0x43 => AddressingMode::Indirect_X,
+        0x53 => AddressingMode::Indirect_Y,
+        // RRA
+        0x67 => AddressingMode::ZeroPage,
+        0x77 => AddressingMode::ZeroPage_X,
+        0x6F => AddressingMode::Absolute,
+        0x7F => AddressingMode::Absolute_X,
+        0x7B => AddressingMode::Absolute_Y,
+        0x63 => AddressingMode::Indirect_X,
+        0x73 => AddressingMode::Indirect_Y,
+        // ANC
+        0x0B => AddressingMode::Immediate,
+        0x2B => AddressingMode::Immediate,
+        // ALR
+        0x4B => AddressingMode::Immediate,
+        // ARR
+        0x6B => AddressingMode::Immediate,
+        // AXS
+        0xCB => AddressingMode::Immediate,
+        // LAS
+        0xBB => AddressingMode::Absolute_Y,
+        // KIL
+        0x02 => AddressingMode::Implied,
+        0x12 => AddressingMode::Implied,
+        0x22 => AddressingMode::Implied,
+        0x32 => AddressingMode::Implied,
+        0x42 => AddressingMode::Implied,
+        0x52 => AddressingMode::Implied,
+        0x62 => AddressingMode::Implied,
+        0x72 => AddressingMode::Implied,
+        0x92 => AddressingMode::Implied,
+        0xB2 => AddressingMode::Implied,
+        0xD2 => AddressingMode::Implied,
+        0xF2 => AddressingMode::Implied,
+        // XAA
+        0x8B => AddressingMode::Immediate,
+        // LXA
+        0xAB => AddressingMode::Immediate,
+        // AHX
+        0x93 => AddressingMode::Indirect_Y,
+        0x9F => AddressingMode::Absolute_Y,
+        // TAS
+        0x9B => AddressingMode::Absolute_Y,
+        // SHY
+        0x9C => AddressingMode::Absolute_X,
+        // SHX
+        0x9E => AddressingMode::Absolute_Y,
     }
 }
 
@@ -329,7 +456,478 @@ pub fn map_instruction_to_name(instruction: u8) -> &'static str {
         0x9A => "TXS",
         // TYA
         0x98 => "TYA",
-        _ => panic!("Got unrecognized instruction while mapping addressing mode. ")
+        // ----- Unofficial instructions -----
+        // NOP
+        0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA | 0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 | 0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 | 0x0C | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => "NOP",
+        // LAX
+        0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => "LAX",
+        // SAX
+        0x87 | 0x97 | 0x8F | 0x83 => "SAX",
+        // SBC
+        0xEB => "SBC",
+        // DCP
+        0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xC3 | 0xD3 => "DCP",
+        // ISB, also known as ISC
+        0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => "ISB",
+        // SLO
+        0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => "SLO",
+        // RLA
+        0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x23 | 0x33 => "RLA",
+        // SRE
+        0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => "SRE",
+        // RRA
+        0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => "RRA",
+        // ANC
+        0x0B | 0x2B => "ANC",
+        // ALR
+        0x4B => "ALR",
+        // ARR
+        0x6B => "ARR",
+        // AXS
+        0xCB => "AXS",
+        // LAS
+        0xBB => "LAS",
+        // KIL
+        0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => "KIL",
+        // XAA
+        0x8B => "XAA",
+        // LXA
+        0xAB => "LXA",
+        // AHX
+        0x93 | 0x9F => "AHX",
+        // TAS
+        0x9B => "TAS",
+        // SHY
+        0x9C => "SHY",
+        // SHX
+        0x9E => "SHX",
+    }
+}
+
+// Base cycle count for each instruction, not including the extra cycle for crossing a 
+//  page on an indexed read or the extra cycles for a taken branch. 
+pub fn map_instruction_to_cycle_count(instruction: u8) -> u8 {
+    match instruction {
+        // ADC
+        0x69 => 2,
+        0x65 => 3,
+        0x75 => 4,
+        0x6D => 4,
+        0x7D => 4,
+        0x79 => 4,
+        0x61 => 6,
+        0x71 => 5,
+        // AND
+        0x29 => 2,
+        0x25 => 3,
+        0x35 => 4,
+        0x2D => 4,
+        0x3D => 4,
+        0x39 => 4,
+        0x21 => 6,
+        0x31 => 5,
+        // ASL
+        0x0A => 2,
+        0x06 => 5,
+        0x16 => 6,
+        0x0E => 6,
+        0x1E => 7,
+        // BCC
+        0x90 => 2,
+        // BCS
+        0xB0 => 2,
+        // BEQ
+        0xF0 => 2,
+        // BIT
+        0x24 => 3,
+        0x2C => 4,
+        // BMI
+        0x30 => 2,
+        // BNE
+        0xD0 => 2,
+        // BPL
+        0x10 => 2,
+        // BRK
+        0x00 => 7,
+        // BVC
+        0x50 => 2,
+        // BVS
+        0x70 => 2,
+        // CLC
+        0x18 => 2,
+        // CLD
+        0xD8 => 2,
+        // CLI
+        0x58 => 2,
+        // CLV
+        0xB8 => 2,
+        // CMP
+        0xC9 => 2,
+        0xC5 => 3,
+        0xD5 => 4,
+        0xCD => 4,
+        0xDD => 4,
+        0xD9 => 4,
+        0xC1 => 6,
+        0xD1 => 5,
+        // CPX
+        0xE0 => 2,
+        0xE4 => 3,
+        0xEC => 4,
+        // CPY
+        0xC0 => 2,
+        0xC4 => 3,
+        0xCC => 4,
+        // DEC
+        0xC6 => 5,
+        0xD6 => 6,
+        0xCE => 6,
+        0xDE => 7,
+        // DEX
+        0xCA => 2,
+        // DEY
+        0x88 => 2,
+        // EOR
+        0x49 => 2,
+        0x45 => 3,
+        0x55 => 4,
+        0x4D => 4,
+        0x5D => 4,
+        0x59 => 4,
+        0x41 => 6,
+        0x51 => 5,
+        // INC
+        0xE6 => 5,
+        0xF6 => 6,
+        0xEE => 6,
+        0xFE => 7,
+        // INX
+        0xE8 => 2,
+        // INY
+        0xC8 => 2,
+        // JMP
+        0x4C => 3,
+        0x6C => 5,
+        // JSR
+        0x20 => 6,
+        // LDA
+        0xA9 => 2,
+        0xA5 => 3,
+        0xB5 => 4,
+        0xAD => 4,
+        0xBD => 4,
+        0xB9 => 4,
+        0xA1 => 6,
+        0xB1 => 5,
+        // LDX
+        0xA2 => 2,
+        0xA6 => 3,
+        0xB6 => 4,
+        0xAE => 4,
+        0xBE => 4,
+        // LDY
+        0xA0 => 2,
+        0xA4 => 3,
+        0xB4 => 4,
+        0xAC => 4,
+        0xBC => 4,
+        // LSR
+        0x4A => 2,
+        0x46 => 5,
+        0x56 => 6,
+        0x4E => 6,
+        0x5E => 7,
+        // NOP
+        0xEA => 2,
+        // ORA
+        0x09 => 2,
+        0x05 => 3,
+        0x15 => 4,
+        0x0D => 4,
+        0x1D => 4,
+        0x19 => 4,
+        0x01 => 6,
+        0x11 => 5,
+        // PHA
+        0x48 => 3,
+        // PHP
+        0x08 => 3,
+        // PLA
+        0x68 => 4,
+        // PLP
+        0x28 => 4,
+        // ROL
+        0x2A => 2,
+        0x26 => 5,
+        0x36 => 6,
+        0x2E => 6,
+        0x3E => 7,
+        // ROR
+        0x6A => 2,
+        0x66 => 5,
+        0x76 => 6,
+        0x6E => 6,
+        0x7E => 7,
+        // RTI
+        0x40 => 6,
+        // RTS
+        0x60 => 6,
+        // SBC
+        0xE9 => 2,
+        0xE5 => 3,
+        0xF5 => 4,
+        0xED => 4,
+        0xFD => 4,
+        0xF9 => 4,
+        0xE1 => 6,
+        0xF1 => 5,
+        // SEC
+        0x38 => 2,
+        // SED
+        0xF8 => 2,
+        // SEI
+        0x78 => 2,
+        // STA
+        0x85 => 3,
+        0x95 => 4,
+        0x8D => 4,
+        0x9D => 5,
+        0x99 => 5,
+        0x81 => 6,
+        0x91 => 6,
+        // STX
+        0x86 => 3,
+        0x96 => 4,
+        0x8E => 4,
+        // STY
+        0x84 => 3,
+        0x94 => 4,
+        0x8C => 4,
+        // TAX
+        0xAA => 2,
+        // TAY
+        0xA8 => 2,
+        // TSX
+        0xBA => 2,
+        // TXA
+        0x8A => 2,
+        // TXS
+        0x9A => 2,
+        // TYA
+        0x98 => 2,
+        // ----- Unofficial instructions -----
+        // NOP
+        0x1A => 2,
+        0x3A => 2,
+        0x5A => 2,
+        0x7A => 2,
+        0xDA => 2,
+        0xFA => 2,
+        0x80 => 2,
+        0x82 => 2,
+        0x89 => 2,
+        0xC2 => 2,
+        0xE2 => 2,
+        0x04 => 3,
+        0x44 => 3,
+        0x64 => 3,
+        0x14 => 4,
+        0x34 => 4,
+        0x54 => 4,
+        0x74 => 4,
+        0xD4 => 4,
+        0xF4 => 4,
+        0x0C => 4,
+        0x1C => 4,
+        0x3C => 4,
+        0x5C => 4,
+        0x7C => 4,
+        0xDC => 4,
+        0xFC => 4,
+        // LAX
+        0xA7 => 3,
+        0xB7 => 4,
+        0xAF => 4,
+        0xBF => 4,
+        0xA3 => 6,
+        0xB3 => 5,
+        // SAX
+        0x87 => 3,
+        0x97 => 4,
+        0x8F => 4,
+        0x83 => 6,
+        // SBC
+        0xEB => 2,
+        // DCP
+        0xC7 => 5,
+        0xD7 => 6,
+        0xCF => 6,
+        0xDF => 7,
+        0xDB => 7,
+        0xC3 => 8,
+        0xD3 => 8,
+        // ISB
+        0xE7 => 5,
+        0xF7 => 6,
+        0xEF => 6,
+        0xFF => 7,
+        0xFB => 7,
+        0xE3 => 8,
+        0xF3 => 8,
+        // SLO
+        0x07 => 5,
+        0x17 => 6,
+        0x0F => 6,
+        0x1F => 7,
+        0x1B => 7,
+        0x03 => 8,
+        0x13 => 8,
+        // RLA
+        0x27 => 5,
+        0x37 => 6,
+        0x2F => 6,
+        0x3F => 7,
+        0x3B => 7,
+        0x23 => 8,
+        0x33 => 8,
+        // SRE
+        0x47 => 5,
+        0x57 => 6,
+        0x4F => 6,
+        0x5F => 7,
+        0x5B => 7,
+        0x43 => 8,
+        0x53 => 8,
+        // RRA
+        0x67 => 5,
+        0x77 => 6,
+        0x6F => 6,
+        0x7F => 7,
+        0x7B => 7,
+        0x63 => 8,
+        0x73 => 8,
+        // ANC
+        0x0B => 2,
+        0x2B => 2,
+        // ALR
+        0x4B => 2,
+        // ARR
+        0x6B => 2,
+        // AXS
+        0xCB => 2,
+        // LAS
+        0xBB => 4,
+        // KIL
+        0x02 => 2,
+        0x12 => 2,
+        0x22 => 2,
+        0x32 => 2,
+        0x42 => 2,
+        0x52 => 2,
+        0x62 => 2,
+        0x72 => 2,
+        0x92 => 2,
+        0xB2 => 2,
+        0xD2 => 2,
+        0xF2 => 2,
+        // XAA
+        0x8B => 2,
+        // LXA
+        0xAB => 2,
+        // AHX
+        0x93 => 6,
+        0x9F => 5,
+        // TAS
+        0x9B => 5,
+        // SHY
+        0x9C => 5,
+        // SHX
+        0x9E => 5,
+    }
+}
+
+// Indexed reads take an extra cycle when adding the index carries into the high byte 
+//  of the address. Stores and read-modify-write instructions always pay for it so 
+//  their base counts already include it. 
+pub fn instruction_has_page_cross_penalty(instruction: u8) -> bool {
+    match instruction {
+        // ADC
+        0x7D | 0x79 | 0x71 => true,
+        // AND
+        0x3D | 0x39 | 0x31 => true,
+        // CMP
+        0xDD | 0xD9 | 0xD1 => true,
+        // EOR
+        0x5D | 0x59 | 0x51 => true,
+        // LDA
+        0xBD | 0xB9 | 0xB1 => true,
+        // LDX
+        0xBE => true,
+        // LDY
+        0xBC => true,
+        // ORA
+        0x1D | 0x19 | 0x11 => true,
+        // SBC
+        0xFD | 0xF9 | 0xF1 => true,
+        // NOP (unofficial)
+        0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => true,
+        // LAX
+        0xBF | 0xB3 => true,
+        // LAS
+        0xBB => true,
+        _ => false
+    }
+}
+
+// Everything outside of the 151 official opcodes. Most of these are side effects of how 
+//  the 6502 decodes instructions, but a good chunk of them are stable enough that games use them. 
+pub fn is_unofficial_instruction(instruction: u8) -> bool {
+    match instruction {
+        // NOP
+        0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA | 0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 | 0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 | 0x0C | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => true,
+        // LAX
+        0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => true,
+        // SAX
+        0x87 | 0x97 | 0x8F | 0x83 => true,
+        // SBC
+        0xEB => true,
+        // DCP
+        0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xC3 | 0xD3 => true,
+        // ISB, also known as ISC
+        0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => true,
+        // SLO
+        0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => true,
+        // RLA
+        0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x23 | 0x33 => true,
+        // SRE
+        0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => true,
+        // RRA
+        0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => true,
+        // ANC
+        0x0B | 0x2B => true,
+        // ALR
+        0x4B => true,
+        // ARR
+        0x6B => true,
+        // AXS
+        0xCB => true,
+        // LAS
+        0xBB => true,
+        // KIL
+        0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => true,
+        // XAA
+        0x8B => true,
+        // LXA
+        0xAB => true,
+        // AHX
+        0x93 | 0x9F => true,
+        // TAS
+        0x9B => true,
+        // SHY
+        0x9C => true,
+        // SHX
+        0x9E => true,
+        _ => false
     }
 }
 
@@ -348,7 +946,6 @@ pub fn addressing_mode_to_program_counter_advancement_amount(mode: AddressingMod
         AddressingMode::Indirect_Y => 2,
         AddressingMode::Indirect => 3,
         AddressingMode::Relative => 0,
-        _ => 0
     }
 }
 
@@ -371,6 +968,7 @@ pub fn print_bytes_in_hex(bytes: &Vec<u8>, num_bytes: u32, grouping: u8) {
     }
 }
 
+#[allow(dead_code)]
 pub fn print_memory_block(bytes: &Vec<u8>, bytes_per_line: u8, display_line_numbers: bool, start_line_numbers_at: u32, hide_zeros: bool) {
     let mut byte_position: u32 = start_line_numbers_at; 
 